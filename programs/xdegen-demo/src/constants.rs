@@ -2,3 +2,94 @@ use anchor_lang::prelude::*;
 
 #[constant]
 pub const SEED: &str = "anchor";
+
+// whole XDEGEN seeded as virtual reserves into every new bonding curve
+#[constant]
+pub const INITIAL_VIRTUAL_XDEGEN: u64 = 30;
+
+// seconds a wallet waits between faucet claims until update_config changes it
+#[constant]
+pub const DEFAULT_CLAIM_COOLDOWN: i64 = 86_400;
+
+// trade fees in basis points of the XDEGEN leg, until update_config changes them
+#[constant]
+pub const DEFAULT_PROTOCOL_FEE_BPS: u16 = 100;
+#[constant]
+pub const DEFAULT_CREATOR_FEE_BPS: u16 = 100;
+// share of the protocol fee paid to the trader's referrer, in basis points
+#[constant]
+pub const DEFAULT_REFERRAL_FEE_BPS: u16 = 2_000;
+// protocol + creator fee may never take more than 10% of a trade
+#[constant]
+pub const MAX_TOTAL_FEE_BPS: u16 = 1_000;
+#[constant]
+pub const BPS_DENOMINATOR: u64 = 10_000;
+
+// TokenParams.decimals ceiling, and the curve supply bounds in whole tokens scaled by those decimals
+#[constant]
+pub const MAX_DECIMALS: u8 = 9;
+#[constant]
+pub const MIN_TOKEN_SUPPLY: u64 = 1;
+#[constant]
+pub const MAX_TOKEN_SUPPLY: u64 = 1_000_000_000_000u64;
+
+// kept in step with the #[max_len] on Config.admin_signers and AdminProposal.approvals;
+// not an IDL #[constant], which has no usize support
+pub const MAX_ADMIN_SIGNERS: usize = 10;
+// seconds an admin proposal can collect approvals and be executed
+#[constant]
+pub const ADMIN_PROPOSAL_TTL: i64 = 604_800;
+
+// kept in step with the #[max_len] on Config.role_members; not an IDL #[constant] either
+pub const MAX_ROLE_MEMBERS: usize = 10;
+
+// seconds between queueing and executing a vault withdrawal, until update_config changes it
+#[constant]
+pub const DEFAULT_WITHDRAW_DELAY: i64 = 172_800;
+#[constant]
+pub const MIN_WITHDRAW_DELAY: i64 = 3_600;
+#[constant]
+pub const MAX_WITHDRAW_DELAY: i64 = 2_592_000;
+// whole XDEGEN that may leave the vault per withdraw epoch, until update_config changes it
+#[constant]
+pub const DEFAULT_WITHDRAW_CAP: u64 = 1_000;
+// length in seconds of the fixed window the withdraw cap applies to, opened by the first
+// withdrawal after the previous one ended
+#[constant]
+pub const WITHDRAW_EPOCH: i64 = 86_400;
+
+// Config.paused bits, one per pausable instruction
+#[constant]
+pub const PAUSE_BUY: u8 = 1 << 0;
+#[constant]
+pub const PAUSE_MINT_TOKEN: u8 = 1 << 1;
+#[constant]
+pub const PAUSE_SELL: u8 = 1 << 2;
+#[constant]
+pub const PAUSE_CLAIM: u8 = 1 << 3;
+#[constant]
+pub const PAUSE_DEPOSIT: u8 = 1 << 4;
+#[constant]
+pub const PAUSE_WITHDRAW: u8 = 1 << 5;
+#[constant]
+pub const PAUSE_ALL: u8 = PAUSE_BUY
+    | PAUSE_MINT_TOKEN
+    | PAUSE_SELL
+    | PAUSE_CLAIM
+    | PAUSE_DEPOSIT
+    | PAUSE_WITHDRAW;
+
+// RoleMember.roles bits, each gating a group of privileged instructions
+#[constant]
+pub const ROLE_TREASURER: u8 = 1 << 0;
+#[constant]
+pub const ROLE_OPERATOR: u8 = 1 << 1;
+#[constant]
+pub const ROLE_PAUSER: u8 = 1 << 2;
+#[constant]
+pub const ROLE_MODERATOR: u8 = 1 << 3;
+#[constant]
+pub const ROLE_ALL: u8 = ROLE_TREASURER
+    | ROLE_OPERATOR
+    | ROLE_PAUSER
+    | ROLE_MODERATOR;
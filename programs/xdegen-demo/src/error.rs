@@ -21,5 +21,97 @@ pub enum ErrorCode {
     #[msg("Unauthorized")]
     Unauthorized,
     #[msg("Math Overflow")]
-    MathOverflow
+    MathOverflow,
+    #[msg("Insufficient vault funds")]
+    InsufficientVaultFunds,
+    #[msg("Slippage exceeded")]
+    SlippageExceeded,
+    #[msg("Invalid trade tiers")]
+    InvalidTradeTiers,
+    #[msg("Invalid admin")]
+    InvalidAdmin,
+    #[msg("Program paused")]
+    ProgramPaused,
+    #[msg("Invalid pause flags")]
+    InvalidPauseFlags,
+    #[msg("Claim cooldown active")]
+    ClaimCooldownActive,
+    #[msg("Claim cap reached")]
+    ClaimCapReached,
+    #[msg("Invalid fee")]
+    InvalidFee,
+    #[msg("No fees accrued")]
+    NoFeesAccrued,
+    #[msg("Self referral")]
+    SelfReferral,
+    #[msg("Referral loop")]
+    ReferralLoop,
+    #[msg("Invalid referrer")]
+    InvalidReferrer,
+    #[msg("Invalid token program")]
+    InvalidTokenProgram,
+    #[msg("Missing metadata accounts")]
+    MissingMetadataAccounts,
+    #[msg("Max supply exceeded")]
+    MaxSupplyExceeded,
+    #[msg("Fixed supply")]
+    FixedSupply,
+    #[msg("Name too long")]
+    NameTooLong,
+    #[msg("Symbol too long")]
+    SymbolTooLong,
+    #[msg("Uri too long")]
+    UriTooLong,
+    #[msg("Metadata immutable")]
+    MetadataImmutable,
+    #[msg("Invalid symbol")]
+    InvalidSymbol,
+    #[msg("Invalid uri")]
+    InvalidUri,
+    #[msg("Symbol taken")]
+    SymbolTaken,
+    #[msg("Missing symbol record")]
+    MissingSymbolRecord,
+    #[msg("Token account not empty")]
+    TokenAccountNotEmpty,
+    #[msg("Unclaimed creator fees")]
+    UnclaimedCreatorFees,
+    #[msg("No tokens held")]
+    NoTokensHeld,
+    #[msg("Multisig required")]
+    MultisigRequired,
+    #[msg("Invalid admin signers")]
+    InvalidAdminSigners,
+    #[msg("Not admin signer")]
+    NotAdminSigner,
+    #[msg("Already approved")]
+    AlreadyApproved,
+    #[msg("Proposal expired")]
+    ProposalExpired,
+    #[msg("Proposal executed")]
+    ProposalExecuted,
+    #[msg("Threshold not met")]
+    ThresholdNotMet,
+    #[msg("Missing withdraw accounts")]
+    MissingWithdrawAccounts,
+    #[msg("Invalid destination")]
+    InvalidDestination,
+    #[msg("Withdrawal locked")]
+    WithdrawalLocked,
+    #[msg("Withdraw cap exceeded")]
+    WithdrawCapExceeded,
+    #[msg("Missing role")]
+    MissingRole,
+    #[msg("Invalid roles")]
+    InvalidRoles,
+    #[msg("Not upgrade authority")]
+    NotUpgradeAuthority,
+    #[msg("Missing referrer")]
+    MissingReferrer,
+    #[msg("Proposal not expired")]
+    ProposalNotExpired,
+    #[msg("Token delisted")]
+    TokenDelisted,
+    #[msg("Unexpected symbol record")]
+    UnexpectedSymbolRecord
 }
@@ -0,0 +1,275 @@
+use anchor_lang::prelude::*;
+
+use crate::{AdminAction, CurveType, MetadataStandard, TradeTiers, VaultBucket};
+
+#[event]
+pub struct Initialized {
+    pub admin: Pubkey,
+    pub xdegen_mint: Pubkey,
+    pub vault: Pubkey,
+    pub claim_amount: u64,
+    pub trade_tiers: TradeTiers,
+    pub fee_vault: Pubkey,
+    pub protocol_fee_bps: u16,
+    pub creator_fee_bps: u16,
+    pub referral_fee_bps: u16,
+}
+
+#[event]
+pub struct Deposited {
+    pub admin: Pubkey,
+    pub amount: u64,
+    pub bucket: VaultBucket,
+    pub treasury_balance: u64,
+    pub claim_pool: u64,
+}
+
+#[event]
+pub struct Withdrawn {
+    pub admin: Pubkey,
+    pub id: u64,
+    pub destination: Pubkey,
+    pub amount: u64,
+    pub bucket: VaultBucket,
+    pub treasury_balance: u64,
+    pub claim_pool: u64,
+    pub withdrawn_in_epoch: u64,
+}
+
+#[event]
+pub struct WithdrawalQueued {
+    pub admin: Pubkey,
+    pub id: u64,
+    pub destination: Pubkey,
+    pub amount: u64,
+    pub bucket: VaultBucket,
+    pub executable_at: i64,
+}
+
+#[event]
+pub struct WithdrawalCancelled {
+    pub authority: Pubkey,
+    pub id: u64,
+}
+
+#[event]
+pub struct TokenCreated {
+    pub trader: Pubkey,
+    pub mint: Pubkey,
+    pub curve_type: CurveType,
+    pub metadata_standard: MetadataStandard,
+    pub max_supply: u64,
+    pub fixed_supply: bool,
+    pub xdegen_in: u64,
+    pub tokens_out: u64,
+    pub protocol_fee: u64,
+    pub creator_fee: u64,
+    pub referrer: Option<Pubkey>,
+    pub referral_fee: u64,
+    pub balance: u64,
+    pub total_trades: u64,
+    pub total_buys: u64,
+}
+
+#[event]
+pub struct TokensMinted {
+    pub trader: Pubkey,
+    pub mint: Pubkey,
+    pub xdegen_in: u64,
+    pub tokens_out: u64,
+    pub protocol_fee: u64,
+    pub creator_fee: u64,
+    pub referrer: Option<Pubkey>,
+    pub referral_fee: u64,
+    pub balance: u64,
+    pub total_trades: u64,
+    pub total_buys: u64,
+}
+
+#[event]
+pub struct TokensSold {
+    pub trader: Pubkey,
+    pub mint: Pubkey,
+    pub tokens_in: u64,
+    pub xdegen_out: u64,
+    pub protocol_fee: u64,
+    pub creator_fee: u64,
+    pub referrer: Option<Pubkey>,
+    pub referral_fee: u64,
+    // creator's TokenRecord.balance after the sell, 0 once the record is closed
+    pub balance: u64,
+    // seller's token account balance after the burn
+    pub token_account_balance: u64,
+    pub total_trades: u64,
+    pub total_sells: u64,
+}
+
+#[event]
+pub struct Claimed {
+    pub claimer: Pubkey,
+    pub amount: u64,
+    pub wallet_total_claimed: u64,
+    pub claim_count: u64,
+    pub total_claimed: u64,
+}
+
+#[event]
+pub struct ConfigUpdated {
+    pub admin: Pubkey,
+    pub old_claim_amount: u64,
+    pub new_claim_amount: u64,
+    pub old_claim_cooldown: i64,
+    pub new_claim_cooldown: i64,
+    pub old_max_claim_per_wallet: u64,
+    pub new_max_claim_per_wallet: u64,
+    pub old_protocol_fee_bps: u16,
+    pub new_protocol_fee_bps: u16,
+    pub old_creator_fee_bps: u16,
+    pub new_creator_fee_bps: u16,
+    pub old_referral_fee_bps: u16,
+    pub new_referral_fee_bps: u16,
+    pub old_withdraw_delay: i64,
+    pub new_withdraw_delay: i64,
+    pub old_withdraw_cap: u64,
+    pub new_withdraw_cap: u64,
+    pub pending_withdraw_delay: Option<i64>,
+    pub pending_withdraw_cap: Option<u64>,
+    pub pending_withdraw_limits_at: i64,
+}
+
+#[event]
+pub struct TradeTiersUpdated {
+    pub admin: Pubkey,
+    pub old_trade_tiers: TradeTiers,
+    pub new_trade_tiers: TradeTiers,
+}
+
+#[event]
+pub struct PauseUpdated {
+    pub authority: Pubkey,
+    pub old_paused: u8,
+    pub new_paused: u8,
+}
+
+#[event]
+pub struct RolesUpdated {
+    pub admin: Pubkey,
+    pub member: Pubkey,
+    pub old_roles: u8,
+    pub new_roles: u8,
+}
+
+#[event]
+pub struct AdminTransferProposed {
+    pub admin: Pubkey,
+    pub pending_admin: Option<Pubkey>,
+}
+
+#[event]
+pub struct AdminTransferred {
+    pub old_admin: Pubkey,
+    pub new_admin: Pubkey,
+}
+#[event]
+pub struct CreatorFeesClaimed {
+    pub creator: Pubkey,
+    pub mint: Pubkey,
+    pub amount: u64,
+}
+
+#[event]
+pub struct ProtocolFeesCollected {
+    pub admin: Pubkey,
+    pub amount: u64,
+}
+
+#[event]
+pub struct ReferralAccountCreated {
+    pub owner: Pubkey,
+}
+
+#[event]
+pub struct ReferralRewardsClaimed {
+    pub referrer: Pubkey,
+    pub amount: u64,
+    pub total_rewards: u64,
+}
+
+#[event]
+pub struct TokenMetadataUpdated {
+    pub owner: Pubkey,
+    pub mint: Pubkey,
+    pub name: String,
+    pub symbol: String,
+    pub uri: String,
+    pub is_mutable: bool,
+}
+
+
+#[event]
+pub struct SymbolReserved {
+    pub admin: Pubkey,
+    pub symbol: String,
+}
+
+#[event]
+pub struct SymbolReleased {
+    pub admin: Pubkey,
+    pub symbol: String,
+    pub mint: Option<Pubkey>,
+}
+
+#[event]
+pub struct DelistingUpdated {
+    pub admin: Pubkey,
+    pub mint: Pubkey,
+    pub delisted: bool,
+}
+
+#[event]
+pub struct TokenRecordClosed {
+    pub owner: Pubkey,
+    pub mint: Pubkey,
+}
+
+#[event]
+pub struct HolderPositionOpened {
+    pub holder: Pubkey,
+    pub mint: Pubkey,
+}
+
+#[event]
+pub struct AdminProposalCreated {
+    pub id: u64,
+    pub proposer: Pubkey,
+    pub action: AdminAction,
+    pub expires_at: i64,
+}
+
+#[event]
+pub struct AdminProposalApproved {
+    pub id: u64,
+    pub approver: Pubkey,
+    pub approvals: u8,
+    pub threshold: u8,
+}
+
+#[event]
+pub struct AdminProposalExecuted {
+    pub id: u64,
+    pub executor: Pubkey,
+}
+
+#[event]
+pub struct AdminProposalClosed {
+    pub id: u64,
+    pub proposer: Pubkey,
+}
+
+#[event]
+pub struct AdminSignersUpdated {
+    pub old_signers: Vec<Pubkey>,
+    pub new_signers: Vec<Pubkey>,
+    pub old_threshold: u8,
+    pub new_threshold: u8,
+}
\ No newline at end of file
@@ -0,0 +1,32 @@
+use anchor_lang::prelude::*;
+
+use crate::{error::ErrorCode, events::AdminTransferred, Config};
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct AcceptAdmin<'info> {
+    pub new_admin: Signer<'info>,
+    #[account(
+        mut,
+        constraint = config.pending_admin == Some(new_admin.key()) @ ErrorCode::Unauthorized,
+        seeds = [b"config"],
+        bump = config.bump
+    )]
+    pub config: Account<'info, Config>,
+}
+
+pub fn accept_admin_handler(ctx: Context<AcceptAdmin>) -> Result<()> {
+    let config = &mut ctx.accounts.config;
+    let old_admin = config.admin;
+    let new_admin = ctx.accounts.new_admin.key();
+
+    msg!("Admin transferred from {} to {}", old_admin, new_admin);
+    config.transfer_admin(new_admin)?;
+    config.pending_admin = None;
+
+    emit_cpi!(AdminTransferred {
+        old_admin,
+        new_admin,
+    });
+    Ok(())
+}
\ No newline at end of file
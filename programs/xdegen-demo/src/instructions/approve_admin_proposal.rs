@@ -0,0 +1,43 @@
+use anchor_lang::prelude::*;
+
+use crate::{AdminProposal, Config, error::ErrorCode, events::AdminProposalApproved};
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct ApproveAdminProposal<'info> {
+    pub approver: Signer<'info>,
+    #[account(
+        constraint = config.is_admin_signer(&approver.key()) @ ErrorCode::NotAdminSigner,
+        seeds = [b"config"],
+        bump = config.bump
+    )]
+    pub config: Account<'info, Config>,
+    #[account(
+        mut,
+        seeds = [b"admin_proposal", admin_proposal.id.to_le_bytes().as_ref()],
+        bump = admin_proposal.bump
+    )]
+    pub admin_proposal: Account<'info, AdminProposal>,
+}
+
+pub fn approve_admin_proposal_handler(ctx: Context<ApproveAdminProposal>) -> Result<()> {
+    let config = &ctx.accounts.config;
+    let admin_proposal = &mut ctx.accounts.admin_proposal;
+    admin_proposal.require_open(Clock::get()?.unix_timestamp)?;
+
+    let approver = ctx.accounts.approver.key();
+    require!(!admin_proposal.approvals.contains(&approver), ErrorCode::AlreadyApproved);
+
+    // drop approvals of removed signers so the list stays within the signer set's size
+    admin_proposal.approvals.retain(|key| config.is_admin_signer(key));
+    admin_proposal.approvals.push(approver);
+
+    msg!("Admin proposal {} approved by {}", admin_proposal.id, approver);
+    emit_cpi!(AdminProposalApproved {
+        id: admin_proposal.id,
+        approver,
+        approvals: admin_proposal.approvals.len() as u8,
+        threshold: config.admin_threshold,
+    });
+    Ok(())
+}
\ No newline at end of file
@@ -1,6 +1,7 @@
 use anchor_lang::prelude::*;
+use anchor_lang::system_program::{create_account, CreateAccount};
 use anchor_spl::{
-    associated_token::AssociatedToken, 
+    associated_token::{create, AssociatedToken, Create}, 
     metadata::{
         create_metadata_accounts_v3,
         mpl_token_metadata::types::DataV2,
@@ -8,18 +9,39 @@ use anchor_spl::{
         Metadata,
     }, 
     token_interface::{ 
+        initialize_mint2,
+        metadata_pointer_initialize,
         mint_to_checked, 
+        spl_token_2022::{extension::ExtensionType, state::Mint as SplMint},
+        spl_token_metadata_interface::state::TokenMetadata as Token2022Metadata,
+        token_metadata_initialize,
+        InitializeMint2,
         Mint, 
+        MetadataPointerInitialize,
         MintToChecked, 
         TokenAccount, 
         TokenInterface,
+        TokenMetadataInitialize,
         transfer_checked,
         TransferChecked
     }
 };
 use session_keys::{Session, SessionToken};
-use crate::{ALLOWED_AMOUNTS, Config, TokenMetadata, TokenParams, TokenRecord, error::ErrorCode};
+use crate::{
+    INITIAL_VIRTUAL_XDEGEN, 
+    PAUSE_BUY, 
+    BondingCurve, 
+    Config, 
+    MetadataStandard,
+    ReferralAccount,
+    SymbolRecord,
+    TokenParams, 
+    TokenRecord, 
+    error::ErrorCode,
+    events::TokenCreated
+};
 
+#[event_cpi]
 #[derive(Accounts, Session)]
 #[instruction(data: TokenParams)]
 pub struct Buy<'info> {
@@ -34,67 +56,123 @@ pub struct Buy<'info> {
     // delegated account
     #[account(
         mut,
-        has_one = vault,
         has_one = xdegen_mint,
+        has_one = fee_vault,
         seeds = [b"config"],
         bump = config.bump
     )]
     pub config: Account<'info, Config>,
+    // created in the handler, the account layout depends on data.metadata_standard
+    #[account(mut)]
+    pub mint: Signer<'info>,
+    /// CHECK: PDA that owns mint, freeze and metadata update authority over the created token
     #[account(
-        init,
-        payer = trader,
-        mint::decimals = data.decimals,
-        mint::authority = trader
+        seeds = [b"mint_authority", mint.key().as_ref()],
+        bump
     )]
-    pub mint: InterfaceAccount<'info, Mint>,
+    pub mint_authority: UncheckedAccount<'info>,
+    /// CHECK: created in the handler once the mint exists, the associated token program checks the address
+    #[account(mut)]
+    pub trader_mint_ata: UncheckedAccount<'info>,
+    pub xdegen_mint: InterfaceAccount<'info, Mint>,
     #[account(
         init,
         payer = trader,
-        associated_token::mint = mint,
-        associated_token::authority = trader,
-        associated_token::token_program = token_program
+        token::mint = xdegen_mint,
+        token::authority = reserve,
+        token::token_program = token_program,
+        seeds = [b"reserve", mint.key().as_ref()],
+        bump
     )]
-    pub trader_mint_ata: InterfaceAccount<'info, TokenAccount>,
-    pub xdegen_mint: InterfaceAccount<'info, Mint>,
+    pub reserve: Box<InterfaceAccount<'info, TokenAccount>>,
     #[account(mut)]
-    pub vault: InterfaceAccount<'info, TokenAccount>,
+    pub fee_vault: Box<InterfaceAccount<'info, TokenAccount>>,
     #[account(mut)]
     pub trader_xdegen_ata: InterfaceAccount<'info, TokenAccount>,
 
     #[account(
         init,
-        space = TokenRecord::INIT_SPACE,
+        space = 8 + TokenRecord::INIT_SPACE,
         payer = trader,
         seeds = [b"token_record", trader.key().as_ref(), mint.key().as_ref()],
         bump
     )]
     pub token_record: Account<'info, TokenRecord>,
 
-    /// CHECK: Metaplex mint metdata
+    #[account(
+        init,
+        space = 8 + BondingCurve::INIT_SPACE,
+        payer = trader,
+        seeds = [b"bonding_curve", mint.key().as_ref()],
+        bump
+    )]
+    pub bonding_curve: Account<'info, BondingCurve>,
+
+    // registered to the new mint in the handler, fails with SymbolTaken if the symbol is held
+    #[account(
+        init_if_needed,
+        space = 8 + SymbolRecord::INIT_SPACE,
+        payer = trader,
+        seeds = [b"symbol", SymbolRecord::seed(&data.symbol)],
+        bump
+    )]
+    pub symbol_record: Box<Account<'info, SymbolRecord>>,
+
+    /// CHECK: the trader's ReferralAccount, see Config::split_referral_fee
+    #[account(
+        mut,
+        seeds = [b"referral", trader.key().as_ref()],
+        bump
+    )]
+    pub trader_referral: UncheckedAccount<'info>,
+    #[account(
+        mut,
+        seeds = [b"referral", referrer_referral.owner.as_ref()],
+        bump = referrer_referral.bump
+    )]
+    pub referrer_referral: Option<Box<Account<'info, ReferralAccount>>>,
+
+    /// CHECK: Metaplex mint metdata, only used with MetadataStandard::Metaplex
     #[account(mut)]
-    pub metadata: UncheckedAccount<'info>,
+    pub metadata: Option<UncheckedAccount<'info>>,
     
     pub rent: Sysvar<'info, Rent>,
     pub associated_token_program: Program<'info, AssociatedToken>,
-    pub token_metadata_program: Program<'info, Metadata>,
+    pub token_metadata_program: Option<Program<'info, Metadata>>,
     pub token_program: Interface<'info, TokenInterface>,
+    // owner of the created mint, SPL Token for Metaplex and Token-2022 otherwise
+    pub mint_token_program: Interface<'info, TokenInterface>,
     pub system_program: Program<'info, System>, 
 }
 
 pub fn buy_handler(
     ctx: Context<Buy>, 
     data: TokenParams,
-    amount: u64
+    amount: u64,
+    min_tokens_out: u64
 ) -> Result<()> {
     let config = &mut ctx.accounts.config;
+    config.require_not_paused(PAUSE_BUY)?;
 
-    require!(data.name.as_bytes().len() > 0, ErrorCode::NameLengthZero);
-    require!(data.symbol.as_bytes().len() > 0, ErrorCode::SymbolLengthZero);
-    require!(data.supply > 0, ErrorCode::InvalidSupply);
-    require!(data.decimals <= 9, ErrorCode::InvalidDecimals);
+    data.validate()?;
+    ctx.accounts.symbol_record.register(
+        data.symbol.clone(),
+        Some(ctx.accounts.mint.key()),
+        ctx.bumps.symbol_record
+    )?;
+
+    let expected_mint_program = match data.metadata_standard {
+        MetadataStandard::Metaplex => anchor_spl::token::ID,
+        MetadataStandard::Token2022 => anchor_spl::token_2022::ID,
+    };
+    require_keys_eq!(
+        ctx.accounts.mint_token_program.key(),
+        expected_mint_program,
+        ErrorCode::InvalidTokenProgram
+    );
 
     require!(
-        amount > 0 && ALLOWED_AMOUNTS.contains(&amount), 
+        amount > 0 && config.trade_tiers.is_tier(amount), 
         ErrorCode::InvalidAmount
     );
     require!(
@@ -102,46 +180,211 @@ pub fn buy_handler(
         ErrorCode::InsufficientFunds
     );
 
-    msg!("Transfer buy token to vault");
+    let virtual_xdegen_reserves = INITIAL_VIRTUAL_XDEGEN
+        .checked_mul(10u64.pow(ctx.accounts.xdegen_mint.decimals as u32))
+        .ok_or(ErrorCode::MathOverflow)?;
+
+    let bonding_curve = &mut ctx.accounts.bonding_curve;
+    bonding_curve.set_inner(BondingCurve {
+        mint: ctx.accounts.mint.key(),
+        creator: ctx.accounts.trader.key(),
+        curve_type: data.curve_type,
+        reserve: ctx.accounts.reserve.key(),
+        reserve_bump: ctx.bumps.reserve,
+        mint_authority_bump: ctx.bumps.mint_authority,
+        virtual_xdegen_reserves,
+        virtual_token_reserves: data.supply,
+        real_xdegen_reserves: 0,
+        tokens_sold: 0,
+        delisted: false,
+        bump: ctx.bumps.bonding_curve
+    });
+
+    let (mut protocol_fee, creator_fee) = config.trade_fees(amount)?;
+    let fees = protocol_fee + creator_fee;
+    let net_amount = amount - fees;
+
+    let mut trader_referral = ReferralAccount::load(&ctx.accounts.trader_referral)?;
+    let referral_fee = config.split_referral_fee(
+        trader_referral.as_mut(),
+        ctx.accounts.referrer_referral.as_deref_mut().map(|r| &mut **r),
+        protocol_fee
+    )?;
+    protocol_fee -= referral_fee;
+    if let Some(trader_referral) = trader_referral {
+        trader_referral.store(&ctx.accounts.trader_referral)?;
+    }
+
+    let tokens_out = bonding_curve.tokens_out(net_amount)?;
+    require!(tokens_out > 0, ErrorCode::InvalidAmount);
+    require!(tokens_out >= min_tokens_out, ErrorCode::SlippageExceeded);
+    require!(tokens_out <= data.max_supply, ErrorCode::MaxSupplyExceeded);
+    bonding_curve.record_buy(net_amount, tokens_out)?;
+
+    if fees > 0 {
+        msg!("Transfer trade fees {} to fee vault", fees);
+        transfer_checked(
+            CpiContext::new(
+                ctx.accounts.token_program.to_account_info(),
+                TransferChecked {
+                    from: ctx.accounts.trader_xdegen_ata.to_account_info(),
+                    to: ctx.accounts.fee_vault.to_account_info(),
+                    mint: ctx.accounts.xdegen_mint.to_account_info(),
+                    authority: ctx.accounts.trader.to_account_info()
+                },
+            ),
+            fees,
+            ctx.accounts.xdegen_mint.decimals
+        )?;
+    }
+
+    msg!("Transfer buy token to reserve");
     transfer_checked(
         CpiContext::new(
             ctx.accounts.token_program.to_account_info(),
             TransferChecked {
                 from: ctx.accounts.trader_xdegen_ata.to_account_info(),
-                to: ctx.accounts.vault.to_account_info(),
+                to: ctx.accounts.reserve.to_account_info(),
                 mint: ctx.accounts.xdegen_mint.to_account_info(),
                 authority: ctx.accounts.trader.to_account_info()
             },
         ),
-        amount,
+        net_amount,
         ctx.accounts.xdegen_mint.decimals
     )?;
 
+    let mint = ctx.accounts.mint.key();
+    let signer_seeds: &[&[&[u8]]] = &[&[
+        b"mint_authority".as_ref(),
+        mint.as_ref(),
+        &[ctx.bumps.mint_authority],
+    ]];
+
+    let mint_authority = ctx.accounts.mint_authority.key();
+    let (extensions, metadata_len) = match data.metadata_standard {
+        MetadataStandard::Metaplex => (vec![], 0),
+        MetadataStandard::Token2022 => {
+            let token_metadata = Token2022Metadata {
+                name: data.name.clone(),
+                symbol: data.symbol.clone(),
+                uri: data.uri.clone(),
+                ..Default::default()
+            };
+            (vec![ExtensionType::MetadataPointer], token_metadata.tlv_size_of()?)
+        }
+    };
+    let space = ExtensionType::try_calculate_account_len::<SplMint>(&extensions)?;
+
+    // token_metadata_initialize grows the mint itself, so its rent is paid up front
+    msg!("Creating mint {}", mint);
+    create_account(
+        CpiContext::new(
+            ctx.accounts.system_program.to_account_info(),
+            CreateAccount {
+                from: ctx.accounts.trader.to_account_info(),
+                to: ctx.accounts.mint.to_account_info()
+            }
+        ),
+        Rent::get()?.minimum_balance(space + metadata_len),
+        space as u64,
+        ctx.accounts.mint_token_program.key
+    )?;
+
+    if data.metadata_standard == MetadataStandard::Token2022 {
+        metadata_pointer_initialize(
+            CpiContext::new(
+                ctx.accounts.mint_token_program.to_account_info(),
+                MetadataPointerInitialize {
+                    token_program_id: ctx.accounts.mint_token_program.to_account_info(),
+                    mint: ctx.accounts.mint.to_account_info()
+                }
+            ),
+            Some(mint_authority),
+            Some(mint)
+        )?;
+    }
+
+    initialize_mint2(
+        CpiContext::new(
+            ctx.accounts.mint_token_program.to_account_info(),
+            InitializeMint2 {
+                mint: ctx.accounts.mint.to_account_info()
+            }
+        ),
+        data.decimals,
+        &mint_authority,
+        Some(&mint_authority)
+    )?;
+
     msg!("Adding token metadata");
-    create_metadata_accounts_v3(
+    match data.metadata_standard {
+        MetadataStandard::Metaplex => {
+            let (Some(metadata), Some(token_metadata_program)) = (
+                ctx.accounts.metadata.as_ref(),
+                ctx.accounts.token_metadata_program.as_ref()
+            ) else {
+                return err!(ErrorCode::MissingMetadataAccounts);
+            };
+
+            create_metadata_accounts_v3(
+                CpiContext::new_with_signer(
+                token_metadata_program.to_account_info(),
+                 CreateMetadataAccountsV3 {
+                    payer: ctx.accounts.trader.to_account_info(),
+                    update_authority: ctx.accounts.mint_authority.to_account_info(),
+                    mint: ctx.accounts.mint.to_account_info(),
+                    mint_authority: ctx.accounts.mint_authority.to_account_info(),
+                    metadata: metadata.to_account_info(),
+                    system_program: ctx.accounts.system_program.to_account_info(),
+                    rent: ctx.accounts.rent.to_account_info()
+                },
+                signer_seeds),
+                DataV2 {
+                    name: data.name.clone(),
+                    symbol: data.symbol.clone(),
+                    uri: data.uri.clone(),
+                    seller_fee_basis_points: 0,
+                    creators: None,
+                    collection: None,
+                    uses: None,
+                },
+                true,
+                true,
+                None
+            )?;
+        }
+        MetadataStandard::Token2022 => {
+            token_metadata_initialize(
+                CpiContext::new_with_signer(
+                    ctx.accounts.mint_token_program.to_account_info(),
+                    TokenMetadataInitialize {
+                        program_id: ctx.accounts.mint_token_program.to_account_info(),
+                        metadata: ctx.accounts.mint.to_account_info(),
+                        update_authority: ctx.accounts.mint_authority.to_account_info(),
+                        mint_authority: ctx.accounts.mint_authority.to_account_info(),
+                        mint: ctx.accounts.mint.to_account_info()
+                    },
+                    signer_seeds
+                ),
+                data.name.clone(),
+                data.symbol.clone(),
+                data.uri.clone()
+            )?;
+        }
+    }
+
+    create(
         CpiContext::new(
-        ctx.accounts.token_metadata_program.to_account_info(),
-         CreateMetadataAccountsV3 {
-            payer: ctx.accounts.trader.to_account_info(),
-            update_authority: ctx.accounts.trader.to_account_info(),
-            mint: ctx.accounts.mint.to_account_info(),
-            mint_authority: ctx.accounts.trader.to_account_info(),
-            metadata: ctx.accounts.metadata.to_account_info(),
-            system_program: ctx.accounts.system_program.to_account_info(),
-            rent: ctx.accounts.rent.to_account_info()
-        }),
-        DataV2 {
-            name: data.name.clone(),
-            symbol: data.symbol.clone(),
-            uri: data.uri.clone(),
-            seller_fee_basis_points: 0,
-            creators: None,
-            collection: None,
-            uses: None,
-        },
-        false,
-        true,
-        None
+            ctx.accounts.associated_token_program.to_account_info(),
+            Create {
+                payer: ctx.accounts.trader.to_account_info(),
+                associated_token: ctx.accounts.trader_mint_ata.to_account_info(),
+                authority: ctx.accounts.trader.to_account_info(),
+                mint: ctx.accounts.mint.to_account_info(),
+                system_program: ctx.accounts.system_program.to_account_info(),
+                token_program: ctx.accounts.mint_token_program.to_account_info()
+            }
+        )
     )?;
 
     msg!(
@@ -150,18 +393,25 @@ pub fn buy_handler(
         ctx.accounts.trader.key()
     );
     mint_to_checked(
-        CpiContext::new(
-            ctx.accounts.token_program.to_account_info(),
+        CpiContext::new_with_signer(
+            ctx.accounts.mint_token_program.to_account_info(),
             MintToChecked {
                 mint: ctx.accounts.mint.to_account_info(),
                 to: ctx.accounts.trader_mint_ata.to_account_info(),
-                authority: ctx.accounts.trader.to_account_info()
-            }
+                authority: ctx.accounts.mint_authority.to_account_info()
+            },
+            signer_seeds
         ), 
-        data.supply, 
-        ctx.accounts.mint.decimals
+        tokens_out, 
+        data.decimals
     )?;
 
+    config.total_reserves = config.total_reserves
+        .checked_add(net_amount)
+        .ok_or(ErrorCode::MathOverflow)?;
+
+    config.accrue_fees(protocol_fee, creator_fee, referral_fee)?;
+
     config.total_trades = config.total_trades
         .checked_add(1)
         .ok_or(ErrorCode::MathOverflow)?;
@@ -173,16 +423,32 @@ pub fn buy_handler(
     ctx.accounts.token_record.set_inner(TokenRecord {
         mint: ctx.accounts.mint.key(),
         owner: ctx.accounts.trader.key(),
-        balance: data.supply,
-        metadata: TokenMetadata {
-            name: data.name,
-            symbol: data.symbol,
-            uri: data.uri,
-            decimals: data.decimals,
-        },
+        balance: tokens_out,
+        metadata: data.metadata(),
+        max_supply: data.max_supply,
+        fixed_supply: data.fixed_supply,
         created_at: Clock::get()?.unix_timestamp,
+        creator_fees: creator_fee,
         bump: ctx.bumps.token_record
     });
 
+    emit_cpi!(TokenCreated {
+        trader: ctx.accounts.trader.key(),
+        mint: ctx.accounts.mint.key(),
+        curve_type: ctx.accounts.bonding_curve.curve_type,
+        metadata_standard: ctx.accounts.token_record.metadata.standard,
+        max_supply: data.max_supply,
+        fixed_supply: data.fixed_supply,
+        xdegen_in: amount,
+        tokens_out,
+        protocol_fee,
+        creator_fee,
+        referrer: ctx.accounts.referrer_referral.as_ref().map(|r| r.owner),
+        referral_fee,
+        balance: ctx.accounts.token_record.balance,
+        total_trades: ctx.accounts.config.total_trades,
+        total_buys: ctx.accounts.config.total_buys,
+    });
+
     Ok(())
 }
\ No newline at end of file
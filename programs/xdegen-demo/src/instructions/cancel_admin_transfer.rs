@@ -0,0 +1,28 @@
+use anchor_lang::prelude::*;
+
+use crate::{error::ErrorCode, events::AdminTransferProposed, Config};
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct CancelAdminTransfer<'info> {
+    pub admin: Signer<'info>,
+    #[account(
+        mut,
+        has_one = admin @ ErrorCode::Unauthorized,
+        constraint = config.pending_admin.is_some() @ ErrorCode::InvalidAdmin,
+        seeds = [b"config"],
+        bump = config.bump
+    )]
+    pub config: Account<'info, Config>,
+}
+
+pub fn cancel_admin_transfer_handler(ctx: Context<CancelAdminTransfer>) -> Result<()> {
+    msg!("Admin transfer cancelled");
+    ctx.accounts.config.pending_admin = None;
+
+    emit_cpi!(AdminTransferProposed {
+        admin: ctx.accounts.admin.key(),
+        pending_admin: None,
+    });
+    Ok(())
+}
\ No newline at end of file
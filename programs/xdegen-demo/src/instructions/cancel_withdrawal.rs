@@ -0,0 +1,36 @@
+use anchor_lang::prelude::*;
+
+use crate::{error::ErrorCode, events::WithdrawalCancelled, Config, PendingWithdrawal};
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct CancelWithdrawal<'info> {
+    pub authority: Signer<'info>,
+    #[account(
+        constraint = config.can_manage_withdrawals(&authority.key()) @ ErrorCode::MissingRole,
+        seeds = [b"config"],
+        bump = config.bump
+    )]
+    pub config: Account<'info, Config>,
+    #[account(
+        mut,
+        close = payer,
+        has_one = payer,
+        seeds = [b"pending_withdrawal", pending_withdrawal.id.to_le_bytes().as_ref()],
+        bump = pending_withdrawal.bump
+    )]
+    pub pending_withdrawal: Account<'info, PendingWithdrawal>,
+    /// CHECK: receives the pending withdrawal rent, checked against pending_withdrawal.payer
+    #[account(mut)]
+    pub payer: UncheckedAccount<'info>
+}
+
+pub fn cancel_withdrawal_handler(ctx: Context<CancelWithdrawal>) -> Result<()> {
+    msg!("Withdrawal {} cancelled", ctx.accounts.pending_withdrawal.id);
+
+    emit_cpi!(WithdrawalCancelled {
+        authority: ctx.accounts.authority.key(),
+        id: ctx.accounts.pending_withdrawal.id,
+    });
+    Ok(())
+}
\ No newline at end of file
@@ -11,8 +11,9 @@ use anchor_spl::{
 };
 
 use session_keys::{Session, SessionToken};
-use crate::{ Config, error::ErrorCode };
+use crate::{ PAUSE_CLAIM, ClaimRecord, Config, VaultBucket, error::ErrorCode, events::Claimed };
 
+#[event_cpi]
 #[derive(Accounts, Session)]
 pub struct Claim<'info> {
     #[session(
@@ -26,6 +27,7 @@ pub struct Claim<'info> {
     // delegated account
     #[account(
         mut,
+        has_one = vault,
         has_one = xdegen_mint @ ErrorCode::InvalidMint,
         seeds = [b"config"],
         bump = config.bump
@@ -42,6 +44,14 @@ pub struct Claim<'info> {
         associated_token::authority = claimer
     )]
     pub claimer_xdegen_ata: InterfaceAccount<'info, TokenAccount>,
+    #[account(
+        init_if_needed,
+        payer = claimer,
+        space = 8 + ClaimRecord::INIT_SPACE,
+        seeds = [b"claim_record", claimer.key().as_ref()],
+        bump
+    )]
+    pub claim_record: Account<'info, ClaimRecord>,
     pub associated_token_program: Program<'info, AssociatedToken>,
     pub token_program: Interface<'info, TokenInterface>,
     pub system_program: Program<'info, System>,
@@ -49,9 +59,27 @@ pub struct Claim<'info> {
 
 pub fn claim_handler(ctx: Context<Claim>) -> Result<()> {
     let config = &mut ctx.accounts.config;
+    let claim_record = &mut ctx.accounts.claim_record;
+    config.require_not_paused(PAUSE_CLAIM)?;
+
+    let now = Clock::get()?.unix_timestamp;
+    if claim_record.claim_count > 0 {
+        let next_claim_at = claim_record.last_claimed_at
+            .checked_add(config.claim_cooldown)
+            .ok_or(ErrorCode::MathOverflow)?;
+        require!(now >= next_claim_at, ErrorCode::ClaimCooldownActive);
+    }
+
+    let total_claimed = claim_record.total_claimed
+        .checked_add(config.claim_amount)
+        .ok_or(ErrorCode::MathOverflow)?;
+    require!(total_claimed <= config.max_claim_per_wallet, ErrorCode::ClaimCapReached);
 
     require!(ctx.accounts.vault.amount >= config.claim_amount, ErrorCode::InsufficientFunds);
 
+    let claim_amount = config.claim_amount;
+    config.debit(VaultBucket::ClaimPool, claim_amount)?;
+
     let mint = ctx.accounts.xdegen_mint.key();
     let signer_seeds: &[&[&[u8]]] = &[&[
         b"vault".as_ref(),
@@ -78,5 +106,23 @@ pub fn claim_handler(ctx: Context<Claim>) -> Result<()> {
         .checked_add(config.claim_amount)
         .ok_or(ErrorCode::MathOverflow)?;
 
+    claim_record.set_inner(ClaimRecord {
+        claimer: ctx.accounts.claimer.key(),
+        last_claimed_at: now,
+        total_claimed,
+        claim_count: claim_record.claim_count
+            .checked_add(1)
+            .ok_or(ErrorCode::MathOverflow)?,
+        bump: ctx.bumps.claim_record
+    });
+
+    emit_cpi!(Claimed {
+        claimer: ctx.accounts.claimer.key(),
+        amount: claim_amount,
+        wallet_total_claimed: claim_record.total_claimed,
+        claim_count: claim_record.claim_count,
+        total_claimed: config.total_claimed,
+    });
+
     Ok(())
 }
\ No newline at end of file
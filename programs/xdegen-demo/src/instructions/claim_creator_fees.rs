@@ -0,0 +1,85 @@
+use anchor_lang::prelude::*;
+use anchor_spl::{
+    associated_token::AssociatedToken,
+    token_interface::{Mint, TokenAccount, TokenInterface, transfer_checked, TransferChecked}
+};
+
+use crate::{error::ErrorCode, events::CreatorFeesClaimed, Config, TokenRecord};
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct ClaimCreatorFees<'info> {
+    #[account(mut)]
+    pub creator: Signer<'info>,
+    #[account(
+        mut,
+        has_one = fee_vault,
+        has_one = xdegen_mint @ ErrorCode::InvalidMint,
+        seeds = [b"config"],
+        bump = config.bump
+    )]
+    pub config: Account<'info, Config>,
+    pub xdegen_mint: InterfaceAccount<'info, Mint>,
+    #[account(mut)]
+    pub fee_vault: InterfaceAccount<'info, TokenAccount>,
+    #[account(
+        mut,
+        seeds = [b"token_record", creator.key().as_ref(), token_record.mint.as_ref()],
+        bump = token_record.bump
+    )]
+    pub token_record: Account<'info, TokenRecord>,
+    #[account(
+        init_if_needed,
+        payer = creator,
+        associated_token::mint = xdegen_mint,
+        associated_token::authority = creator,
+        associated_token::token_program = token_program,
+    )]
+    pub creator_xdegen_ata: InterfaceAccount<'info, TokenAccount>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub token_program: Interface<'info, TokenInterface>,
+    pub system_program: Program<'info, System>
+}
+
+pub fn claim_creator_fees_handler(ctx: Context<ClaimCreatorFees>) -> Result<()> {
+    let config = &mut ctx.accounts.config;
+    let token_record = &mut ctx.accounts.token_record;
+
+    let amount = token_record.creator_fees;
+    require!(amount > 0, ErrorCode::NoFeesAccrued);
+
+    token_record.creator_fees = 0;
+    config.creator_fees = config.creator_fees
+        .checked_sub(amount)
+        .ok_or(ErrorCode::MathOverflow)?;
+
+    let mint = ctx.accounts.xdegen_mint.key();
+    let signer_seeds: &[&[&[u8]]] = &[&[
+        b"fee_vault".as_ref(),
+        mint.as_ref(),
+        &[config.fee_vault_bump],
+    ]];
+
+    msg!("Claim creator fees {} for mint {}", amount, token_record.mint);
+    transfer_checked(
+        CpiContext::new_with_signer(
+            ctx.accounts.token_program.to_account_info(),
+            TransferChecked {
+                from: ctx.accounts.fee_vault.to_account_info(),
+                to: ctx.accounts.creator_xdegen_ata.to_account_info(),
+                mint: ctx.accounts.xdegen_mint.to_account_info(),
+                authority: ctx.accounts.fee_vault.to_account_info(),
+            },
+            signer_seeds
+        ),
+        amount,
+        ctx.accounts.xdegen_mint.decimals
+    )?;
+
+    emit_cpi!(CreatorFeesClaimed {
+        creator: ctx.accounts.creator.key(),
+        mint: ctx.accounts.token_record.mint,
+        amount,
+    });
+    Ok(())
+}
\ No newline at end of file
@@ -0,0 +1,85 @@
+use anchor_lang::prelude::*;
+use anchor_spl::{
+    associated_token::AssociatedToken,
+    token_interface::{Mint, TokenAccount, TokenInterface, transfer_checked, TransferChecked}
+};
+
+use crate::{error::ErrorCode, events::ReferralRewardsClaimed, Config, ReferralAccount};
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct ClaimReferralRewards<'info> {
+    #[account(mut)]
+    pub referrer: Signer<'info>,
+    #[account(
+        mut,
+        has_one = fee_vault,
+        has_one = xdegen_mint @ ErrorCode::InvalidMint,
+        seeds = [b"config"],
+        bump = config.bump
+    )]
+    pub config: Account<'info, Config>,
+    pub xdegen_mint: InterfaceAccount<'info, Mint>,
+    #[account(mut)]
+    pub fee_vault: InterfaceAccount<'info, TokenAccount>,
+    #[account(
+        mut,
+        seeds = [b"referral", referrer.key().as_ref()],
+        bump = referral_account.bump
+    )]
+    pub referral_account: Account<'info, ReferralAccount>,
+    #[account(
+        init_if_needed,
+        payer = referrer,
+        associated_token::mint = xdegen_mint,
+        associated_token::authority = referrer,
+        associated_token::token_program = token_program,
+    )]
+    pub referrer_xdegen_ata: InterfaceAccount<'info, TokenAccount>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub token_program: Interface<'info, TokenInterface>,
+    pub system_program: Program<'info, System>
+}
+
+pub fn claim_referral_rewards_handler(ctx: Context<ClaimReferralRewards>) -> Result<()> {
+    let config = &mut ctx.accounts.config;
+    let referral_account = &mut ctx.accounts.referral_account;
+
+    let amount = referral_account.rewards;
+    require!(amount > 0, ErrorCode::NoFeesAccrued);
+
+    referral_account.rewards = 0;
+    config.referral_fees = config.referral_fees
+        .checked_sub(amount)
+        .ok_or(ErrorCode::MathOverflow)?;
+
+    let mint = ctx.accounts.xdegen_mint.key();
+    let signer_seeds: &[&[&[u8]]] = &[&[
+        b"fee_vault".as_ref(),
+        mint.as_ref(),
+        &[config.fee_vault_bump],
+    ]];
+
+    msg!("Claim referral rewards {}", amount);
+    transfer_checked(
+        CpiContext::new_with_signer(
+            ctx.accounts.token_program.to_account_info(),
+            TransferChecked {
+                from: ctx.accounts.fee_vault.to_account_info(),
+                to: ctx.accounts.referrer_xdegen_ata.to_account_info(),
+                mint: ctx.accounts.xdegen_mint.to_account_info(),
+                authority: ctx.accounts.fee_vault.to_account_info(),
+            },
+            signer_seeds
+        ),
+        amount,
+        ctx.accounts.xdegen_mint.decimals
+    )?;
+
+    emit_cpi!(ReferralRewardsClaimed {
+        referrer: ctx.accounts.referrer.key(),
+        amount,
+        total_rewards: ctx.accounts.referral_account.total_rewards,
+    });
+    Ok(())
+}
\ No newline at end of file
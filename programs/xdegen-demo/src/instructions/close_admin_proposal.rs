@@ -0,0 +1,34 @@
+use anchor_lang::prelude::*;
+
+use crate::{AdminProposal, error::ErrorCode, events::AdminProposalClosed};
+
+// anyone may close an expired proposal, the rent always goes back to the proposer
+#[event_cpi]
+#[derive(Accounts)]
+pub struct CloseAdminProposal<'info> {
+    #[account(mut)]
+    pub proposer: SystemAccount<'info>,
+    #[account(
+        mut,
+        close = proposer,
+        has_one = proposer @ ErrorCode::Unauthorized,
+        seeds = [b"admin_proposal", admin_proposal.id.to_le_bytes().as_ref()],
+        bump = admin_proposal.bump
+    )]
+    pub admin_proposal: Account<'info, AdminProposal>,
+}
+
+pub fn close_admin_proposal_handler(ctx: Context<CloseAdminProposal>) -> Result<()> {
+    let admin_proposal = &ctx.accounts.admin_proposal;
+    require!(
+        Clock::get()?.unix_timestamp >= admin_proposal.expires_at,
+        ErrorCode::ProposalNotExpired
+    );
+
+    msg!("Closing expired admin proposal {}", admin_proposal.id);
+    emit_cpi!(AdminProposalClosed {
+        id: admin_proposal.id,
+        proposer: admin_proposal.proposer,
+    });
+    Ok(())
+}
\ No newline at end of file
@@ -0,0 +1,55 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{close_account, CloseAccount, Mint, TokenAccount, TokenInterface};
+
+use crate::{TokenRecord, error::ErrorCode, events::TokenRecordClosed};
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct CloseTokenRecord<'info> {
+    #[account(mut)]
+    pub owner: Signer<'info>,
+    #[account(
+        mut,
+        close = owner,
+        has_one = owner @ ErrorCode::Unauthorized,
+        has_one = mint @ ErrorCode::InvalidMint,
+        seeds = [b"token_record", owner.key().as_ref(), mint.key().as_ref()],
+        bump = token_record.bump
+    )]
+    pub token_record: Account<'info, TokenRecord>,
+    #[account(mint::token_program = mint_token_program)]
+    pub mint: InterfaceAccount<'info, Mint>,
+    #[account(
+        mut,
+        associated_token::mint = mint,
+        associated_token::authority = owner,
+        associated_token::token_program = mint_token_program,
+    )]
+    pub owner_mint_ata: InterfaceAccount<'info, TokenAccount>,
+    pub mint_token_program: Interface<'info, TokenInterface>,
+}
+
+pub fn close_token_record_handler(ctx: Context<CloseTokenRecord>) -> Result<()> {
+    let token_record = &ctx.accounts.token_record;
+    // balance only tracks buy, mint_token and sell and stays above zero for tokens moved out by
+    // transfer, so the emptied ATA is what counts; it has to be empty to be closed anyway
+    require!(ctx.accounts.owner_mint_ata.amount == 0, ErrorCode::TokenAccountNotEmpty);
+    // fees left here would stay counted in Config.creator_fees with nobody able to claim them
+    require!(token_record.creator_fees == 0, ErrorCode::UnclaimedCreatorFees);
+
+    msg!("Closing token record for mint {}", token_record.mint);
+    close_account(CpiContext::new(
+        ctx.accounts.mint_token_program.to_account_info(),
+        CloseAccount {
+            account: ctx.accounts.owner_mint_ata.to_account_info(),
+            destination: ctx.accounts.owner.to_account_info(),
+            authority: ctx.accounts.owner.to_account_info()
+        }
+    ))?;
+
+    emit_cpi!(TokenRecordClosed {
+        owner: ctx.accounts.owner.key(),
+        mint: token_record.mint,
+    });
+    Ok(())
+}
\ No newline at end of file
@@ -0,0 +1,74 @@
+use anchor_lang::prelude::*;
+use anchor_spl::{
+    associated_token::AssociatedToken,
+    token_interface::{Mint, TokenAccount, TokenInterface, transfer_checked, TransferChecked}
+};
+
+use crate::{error::ErrorCode, events::ProtocolFeesCollected, Config, ROLE_TREASURER};
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct CollectProtocolFees<'info> {
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    #[account(
+        mut,
+        has_one = fee_vault,
+        constraint = config.has_role(&authority.key(), ROLE_TREASURER) @ ErrorCode::MissingRole,
+        has_one = xdegen_mint @ ErrorCode::InvalidMint,
+        seeds = [b"config"],
+        bump = config.bump
+    )]
+    pub config: Account<'info, Config>,
+    pub xdegen_mint: InterfaceAccount<'info, Mint>,
+    #[account(mut)]
+    pub fee_vault: InterfaceAccount<'info, TokenAccount>,
+    #[account(
+        mut,
+        associated_token::mint = xdegen_mint,
+        associated_token::authority = authority,
+        associated_token::token_program = token_program,
+    )]
+    pub authority_xdegen_ata: InterfaceAccount<'info, TokenAccount>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub token_program: Interface<'info, TokenInterface>,
+    pub system_program: Program<'info, System>
+}
+
+pub fn collect_protocol_fees_handler(ctx: Context<CollectProtocolFees>) -> Result<()> {
+    let config = &mut ctx.accounts.config;
+    config.require_direct_authority(&ctx.accounts.authority.key())?;
+
+    let amount = config.protocol_fees;
+    require!(amount > 0, ErrorCode::NoFeesAccrued);
+    config.protocol_fees = 0;
+
+    let mint = ctx.accounts.xdegen_mint.key();
+    let signer_seeds: &[&[&[u8]]] = &[&[
+        b"fee_vault".as_ref(),
+        mint.as_ref(),
+        &[config.fee_vault_bump],
+    ]];
+
+    msg!("Collect protocol fees {}", amount);
+    transfer_checked(
+        CpiContext::new_with_signer(
+            ctx.accounts.token_program.to_account_info(),
+            TransferChecked {
+                from: ctx.accounts.fee_vault.to_account_info(),
+                to: ctx.accounts.authority_xdegen_ata.to_account_info(),
+                mint: ctx.accounts.xdegen_mint.to_account_info(),
+                authority: ctx.accounts.fee_vault.to_account_info(),
+            },
+            signer_seeds
+        ),
+        amount,
+        ctx.accounts.xdegen_mint.decimals
+    )?;
+
+    emit_cpi!(ProtocolFeesCollected {
+        admin: ctx.accounts.authority.key(),
+        amount,
+    });
+    Ok(())
+}
\ No newline at end of file
@@ -0,0 +1,68 @@
+use anchor_lang::prelude::*;
+
+use crate::{
+    ADMIN_PROPOSAL_TTL,
+    AdminAction,
+    AdminProposal,
+    Config,
+    validate_admin_signers,
+    error::ErrorCode,
+    events::AdminProposalCreated
+};
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct CreateAdminProposal<'info> {
+    #[account(mut)]
+    pub proposer: Signer<'info>,
+    #[account(
+        mut,
+        constraint = config.is_admin_signer(&proposer.key()) @ ErrorCode::NotAdminSigner,
+        seeds = [b"config"],
+        bump = config.bump
+    )]
+    pub config: Account<'info, Config>,
+    #[account(
+        init,
+        payer = proposer,
+        space = 8 + AdminProposal::INIT_SPACE,
+        seeds = [b"admin_proposal", config.proposal_count.to_le_bytes().as_ref()],
+        bump
+    )]
+    pub admin_proposal: Account<'info, AdminProposal>,
+    pub system_program: Program<'info, System>,
+}
+
+pub fn create_admin_proposal_handler(ctx: Context<CreateAdminProposal>, action: AdminAction) -> Result<()> {
+    let config = &mut ctx.accounts.config;
+    let id = config.proposal_count;
+    config.proposal_count = id
+        .checked_add(1)
+        .ok_or(ErrorCode::MathOverflow)?;
+
+    // catch a bad signer set now rather than after collecting approvals
+    if let AdminAction::SetSigners { signers, threshold } = &action {
+        validate_admin_signers(signers, *threshold)?;
+    }
+
+    let now = Clock::get()?.unix_timestamp;
+    msg!("Admin proposal {} created", id);
+    ctx.accounts.admin_proposal.set_inner(AdminProposal {
+        id,
+        proposer: ctx.accounts.proposer.key(),
+        action: action.clone(),
+        approvals: vec![ctx.accounts.proposer.key()],
+        created_at: now,
+        expires_at: now + ADMIN_PROPOSAL_TTL,
+        executed: false,
+        bump: ctx.bumps.admin_proposal,
+    });
+
+    emit_cpi!(AdminProposalCreated {
+        id,
+        proposer: ctx.accounts.proposer.key(),
+        action,
+        expires_at: now + ADMIN_PROPOSAL_TTL,
+    });
+    Ok(())
+}
\ No newline at end of file
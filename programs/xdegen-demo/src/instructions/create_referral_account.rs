@@ -0,0 +1,35 @@
+use anchor_lang::prelude::*;
+
+use crate::{events::ReferralAccountCreated, ReferralAccount};
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct CreateReferralAccount<'info> {
+    #[account(mut)]
+    pub owner: Signer<'info>,
+    #[account(
+        init,
+        payer = owner,
+        space = 8 + ReferralAccount::INIT_SPACE,
+        seeds = [b"referral", owner.key().as_ref()],
+        bump
+    )]
+    pub referral_account: Account<'info, ReferralAccount>,
+    pub system_program: Program<'info, System>,
+}
+
+pub fn create_referral_account_handler(ctx: Context<CreateReferralAccount>) -> Result<()> {
+    ctx.accounts.referral_account.set_inner(ReferralAccount {
+        owner: ctx.accounts.owner.key(),
+        referrer: None,
+        referral_count: 0,
+        rewards: 0,
+        total_rewards: 0,
+        bump: ctx.bumps.referral_account,
+    });
+
+    emit_cpi!(ReferralAccountCreated {
+        owner: ctx.accounts.owner.key(),
+    });
+    Ok(())
+}
\ No newline at end of file
@@ -1,20 +1,22 @@
 use anchor_lang::prelude::*;
 use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface, TransferChecked, transfer_checked};
 
-use crate::{error::ErrorCode, Config};
+use crate::{error::ErrorCode, events::Deposited, Config, VaultBucket, PAUSE_DEPOSIT, ROLE_TREASURER};
 
+#[event_cpi]
 #[derive(Accounts)]
 pub struct Deposit<'info> {
     #[account(mut)]
-    pub admin: Signer<'info>,
+    pub authority: Signer<'info>,
     #[account(
         mut,
-        has_one = admin @ ErrorCode::Unauthorized,
+        has_one = vault,
+        constraint = config.has_role(&authority.key(), ROLE_TREASURER) @ ErrorCode::MissingRole,
         constraint = config.xdegen_mint == mint.key() @ ErrorCode::CustomError
     )]
     pub config: Account<'info, Config>,
     #[account(mut)]
-    pub admin_token_account: InterfaceAccount<'info, TokenAccount>,
+    pub authority_token_account: InterfaceAccount<'info, TokenAccount>,
     #[account(mut, address=config.xdegen_mint)]
     pub mint: InterfaceAccount<'info, Mint>,
     #[account(mut)]
@@ -22,23 +24,35 @@ pub struct Deposit<'info> {
     pub token_program: Interface<'info, TokenInterface>,
 }
 
-pub fn deposit_handler(ctx: Context<Deposit>, amount: u64) -> Result<()> {
+pub fn deposit_handler(ctx: Context<Deposit>, amount: u64, bucket: VaultBucket) -> Result<()> {
+    ctx.accounts.config.require_not_paused(PAUSE_DEPOSIT)?;
+
     require!(amount > 0, ErrorCode::InvalidAmount);
-    require!(ctx.accounts.admin_token_account.mint == ctx.accounts.mint.key(), ErrorCode::InvalidMint);
-    require!(ctx.accounts.admin_token_account.amount >= amount, ErrorCode::InsufficientFunds);
+    require!(ctx.accounts.authority_token_account.mint == ctx.accounts.mint.key(), ErrorCode::InvalidMint);
+    require!(ctx.accounts.authority_token_account.amount >= amount, ErrorCode::InsufficientFunds);
 
     transfer_checked(
         CpiContext::new(
             ctx.accounts.token_program.to_account_info(),
             TransferChecked {
-                from: ctx.accounts.admin_token_account.to_account_info(),
+                from: ctx.accounts.authority_token_account.to_account_info(),
                 to: ctx.accounts.vault.to_account_info(),
-                authority: ctx.accounts.admin.to_account_info(),
+                authority: ctx.accounts.authority.to_account_info(),
                 mint: ctx.accounts.mint.to_account_info(),
             }
         ),
         amount, 
         ctx.accounts.mint.decimals
     )?;
+
+    ctx.accounts.config.credit(bucket, amount)?;
+
+    emit_cpi!(Deposited {
+        admin: ctx.accounts.authority.key(),
+        amount,
+        bucket,
+        treasury_balance: ctx.accounts.config.treasury_balance,
+        claim_pool: ctx.accounts.config.claim_pool,
+    });
     Ok(())
 }
\ No newline at end of file
@@ -0,0 +1,139 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::TokenAccount;
+
+use crate::{
+    AdminAction,
+    AdminProposal,
+    Config,
+    PendingWithdrawal,
+    apply_config_update,
+    error::ErrorCode,
+    events::{AdminProposalExecuted, AdminSignersUpdated, PauseUpdated, RolesUpdated, TradeTiersUpdated},
+    queue_pending_withdrawal
+};
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct ExecuteAdminProposal<'info> {
+    #[account(mut)]
+    pub executor: Signer<'info>,
+    #[account(
+        mut,
+        constraint = config.is_admin_signer(&executor.key()) @ ErrorCode::NotAdminSigner,
+        seeds = [b"config"],
+        bump = config.bump
+    )]
+    pub config: Account<'info, Config>,
+    // gets the proposal's rent back once it executed
+    #[account(mut)]
+    pub proposer: SystemAccount<'info>,
+    #[account(
+        mut,
+        close = proposer,
+        has_one = proposer @ ErrorCode::Unauthorized,
+        seeds = [b"admin_proposal", admin_proposal.id.to_le_bytes().as_ref()],
+        bump = admin_proposal.bump
+    )]
+    pub admin_proposal: Account<'info, AdminProposal>,
+
+    // AdminAction::Withdraw only, which queues a PendingWithdrawal like queue_withdrawal
+    pub destination: Option<InterfaceAccount<'info, TokenAccount>>,
+    #[account(
+        init,
+        payer = executor,
+        space = 8 + PendingWithdrawal::INIT_SPACE,
+        seeds = [b"pending_withdrawal", config.withdrawal_count.to_le_bytes().as_ref()],
+        bump
+    )]
+    pub pending_withdrawal: Option<Account<'info, PendingWithdrawal>>,
+    pub system_program: Option<Program<'info, System>>,
+}
+
+pub fn execute_admin_proposal_handler(ctx: Context<ExecuteAdminProposal>) -> Result<()> {
+    let config = &mut ctx.accounts.config;
+    let admin_proposal = &mut ctx.accounts.admin_proposal;
+    admin_proposal.require_open(Clock::get()?.unix_timestamp)?;
+    require!(
+        admin_proposal.approval_count(&config.admin_signers) >= config.admin_threshold as usize,
+        ErrorCode::ThresholdNotMet
+    );
+    admin_proposal.executed = true;
+
+    let executor = ctx.accounts.executor.key();
+    msg!("Executing admin proposal {}", admin_proposal.id);
+    match admin_proposal.action.clone() {
+        AdminAction::Withdraw { amount, bucket, destination } => {
+            let (Some(destination_account), Some(pending_withdrawal)) = (
+                ctx.accounts.destination.as_ref(),
+                ctx.accounts.pending_withdrawal.as_mut()
+            ) else {
+                return err!(ErrorCode::MissingWithdrawAccounts);
+            };
+            require_keys_eq!(destination_account.key(), destination, ErrorCode::InvalidDestination);
+            require_keys_eq!(destination_account.mint, config.xdegen_mint, ErrorCode::InvalidMint);
+
+            let event = queue_pending_withdrawal(
+                config,
+                pending_withdrawal,
+                executor,
+                destination,
+                amount,
+                bucket,
+                ctx.bumps.pending_withdrawal.ok_or(ErrorCode::MissingWithdrawAccounts)?
+            )?;
+            emit_cpi!(event);
+        }
+        AdminAction::UpdateConfig { params } => {
+            let event = apply_config_update(config, executor, params)?;
+            emit_cpi!(event);
+        }
+        AdminAction::UpdateTradeTiers { trade_tiers } => {
+            trade_tiers.validate()?;
+            let old_trade_tiers = config.trade_tiers;
+            config.trade_tiers = trade_tiers;
+
+            emit_cpi!(TradeTiersUpdated {
+                admin: executor,
+                old_trade_tiers,
+                new_trade_tiers: trade_tiers,
+            });
+        }
+        AdminAction::SetPause { flags, paused } => {
+            let old_paused = config.set_paused(flags, paused)?;
+
+            emit_cpi!(PauseUpdated {
+                authority: executor,
+                old_paused,
+                new_paused: config.paused,
+            });
+        }
+        AdminAction::SetRoles { member, roles, granted } => {
+            let old_roles = config.set_roles(member, roles, granted)?;
+
+            emit_cpi!(RolesUpdated {
+                admin: executor,
+                member,
+                old_roles,
+                new_roles: config.roles_of(&member),
+            });
+        }
+        AdminAction::SetSigners { signers, threshold } => {
+            let old_signers = config.admin_signers.clone();
+            let old_threshold = config.admin_threshold;
+            config.set_admin_signers(signers, threshold)?;
+
+            emit_cpi!(AdminSignersUpdated {
+                old_signers,
+                new_signers: config.admin_signers.clone(),
+                old_threshold,
+                new_threshold: config.admin_threshold,
+            });
+        }
+    }
+
+    emit_cpi!(AdminProposalExecuted {
+        id: ctx.accounts.admin_proposal.id,
+        executor,
+    });
+    Ok(())
+}
\ No newline at end of file
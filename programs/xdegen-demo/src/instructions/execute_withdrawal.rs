@@ -0,0 +1,90 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface, transfer_checked, TransferChecked};
+
+use crate::{error::ErrorCode, events::Withdrawn, Config, PendingWithdrawal, PAUSE_WITHDRAW};
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct ExecuteWithdrawal<'info> {
+    pub authority: Signer<'info>,
+    #[account(
+        mut,
+        has_one = vault,
+        constraint = config.can_manage_withdrawals(&authority.key()) @ ErrorCode::MissingRole,
+        seeds = [b"config"],
+        bump = config.bump
+    )]
+    pub config: Account<'info, Config>,
+    #[account(
+        mut,
+        close = payer,
+        has_one = payer,
+        has_one = destination @ ErrorCode::InvalidDestination,
+        seeds = [b"pending_withdrawal", pending_withdrawal.id.to_le_bytes().as_ref()],
+        bump = pending_withdrawal.bump
+    )]
+    pub pending_withdrawal: Account<'info, PendingWithdrawal>,
+    /// CHECK: receives the pending withdrawal rent, checked against pending_withdrawal.payer
+    #[account(mut)]
+    pub payer: UncheckedAccount<'info>,
+    #[account(address = config.xdegen_mint @ ErrorCode::InvalidMint)]
+    pub xdegen_mint: InterfaceAccount<'info, Mint>,
+    #[account(mut)]
+    pub vault: InterfaceAccount<'info, TokenAccount>,
+    #[account(mut)]
+    pub destination: InterfaceAccount<'info, TokenAccount>,
+    pub token_program: Interface<'info, TokenInterface>
+}
+
+pub fn execute_withdrawal_handler(ctx: Context<ExecuteWithdrawal>) -> Result<()> {
+    let now = Clock::get()?.unix_timestamp;
+    let pending_withdrawal = &ctx.accounts.pending_withdrawal;
+    pending_withdrawal.require_unlocked(now)?;
+
+    let id = pending_withdrawal.id;
+    let amount = pending_withdrawal.amount;
+    let bucket = pending_withdrawal.bucket;
+
+    let config = &mut ctx.accounts.config;
+    config.require_not_paused(PAUSE_WITHDRAW)?;
+    config.apply_pending_withdraw_limits(now);
+    require!(ctx.accounts.vault.amount >= amount, ErrorCode::InsufficientFunds);
+
+    config.debit(bucket, amount)?;
+    config.record_withdrawal(amount, now)?;
+
+    let mint = ctx.accounts.xdegen_mint.key();
+    let signer_seeds: &[&[&[u8]]] = &[&[
+        b"vault".as_ref(),
+        mint.as_ref(),
+        &[config.vault_bump],
+    ]];
+
+    transfer_checked(
+        CpiContext::new_with_signer(
+            ctx.accounts.token_program.to_account_info(),
+            TransferChecked {
+                from: ctx.accounts.vault.to_account_info(),
+                to: ctx.accounts.destination.to_account_info(),
+                mint: ctx.accounts.xdegen_mint.to_account_info(),
+                authority: ctx.accounts.vault.to_account_info(),
+            },
+            signer_seeds
+        ),
+        amount,
+        ctx.accounts.xdegen_mint.decimals
+    )?;
+
+    msg!("Withdrawal {} executed", id);
+    emit_cpi!(Withdrawn {
+        admin: ctx.accounts.authority.key(),
+        id,
+        destination: ctx.accounts.destination.key(),
+        amount,
+        bucket,
+        treasury_balance: ctx.accounts.config.treasury_balance,
+        claim_pool: ctx.accounts.config.claim_pool,
+        withdrawn_in_epoch: ctx.accounts.config.withdrawn_in_epoch,
+    });
+    Ok(())
+}
\ No newline at end of file
@@ -1,12 +1,35 @@
 use anchor_lang::prelude::*;
 use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
 
-use crate::{Config, error::ErrorCode};
+use crate::{
+    DEFAULT_CLAIM_COOLDOWN,
+    DEFAULT_CREATOR_FEE_BPS,
+    DEFAULT_PROTOCOL_FEE_BPS,
+    DEFAULT_REFERRAL_FEE_BPS,
+    DEFAULT_WITHDRAW_CAP,
+    DEFAULT_WITHDRAW_DELAY,
+    ROLE_ALL,
+    Config,
+    RoleMember,
+    TradeTiers,
+    error::ErrorCode,
+    events::Initialized,
+    program::XdegenDemo
+};
 
+#[event_cpi]
 #[derive(Accounts)]
 pub struct Initialize<'info> {
     #[account(mut)]
     pub admin: Signer<'info>,
+    // only the upgrade authority may create the config, so a fresh deploy cannot be front-run;
+    // event_cpi already claims the `program` field name
+    #[account(constraint = xdegen_demo_program.programdata_address()? == Some(program_data.key()))]
+    pub xdegen_demo_program: Program<'info, XdegenDemo>,
+    #[account(
+        constraint = program_data.upgrade_authority_address == Some(admin.key()) @ ErrorCode::NotUpgradeAuthority
+    )]
+    pub program_data: Account<'info, ProgramData>,
     #[account(
         init,
         payer = admin,
@@ -26,26 +49,96 @@ pub struct Initialize<'info> {
         bump,
     )]
     pub vault: InterfaceAccount<'info, TokenAccount>,
+    #[account(
+        init,
+        payer = admin,
+        token::mint = xdegen_mint,
+        token::authority = fee_vault,
+        seeds = [b"fee_vault", xdegen_mint.key().as_ref()],
+        bump,
+    )]
+    pub fee_vault: InterfaceAccount<'info, TokenAccount>,
     pub token_program: Interface<'info, TokenInterface>,
     pub system_program: Program<'info, System>,
 }
 
 pub fn initialize_handler(ctx: Context<Initialize>) -> Result<()> {
+    let one_token = 10u64.pow(ctx.accounts.xdegen_mint.decimals as u32);
     let claim_amount = 50u64
-        .checked_mul(10u64.pow(ctx.accounts.xdegen_mint.decimals as u32))
+        .checked_mul(one_token)
+        .ok_or(ErrorCode::MathOverflow)?;
+    let withdraw_cap = DEFAULT_WITHDRAW_CAP
+        .checked_mul(one_token)
         .ok_or(ErrorCode::MathOverflow)?;
+    let max_claim_per_wallet = claim_amount
+        .checked_mul(10)
+        .ok_or(ErrorCode::MathOverflow)?;
+
+    // 0.5 to 5 XDEGEN in 0.5 XDEGEN steps
+    let half_token = (one_token / 2).max(1);
+    let trade_tiers = TradeTiers {
+        min_amount: half_token,
+        max_amount: 5u64
+            .checked_mul(one_token)
+            .ok_or(ErrorCode::MathOverflow)?,
+        step: half_token,
+    };
+    trade_tiers.validate()?;
 
     ctx.accounts.config.set_inner(Config {
         admin: ctx.accounts.admin.key(),
+        pending_admin: None,
+        role_members: vec![RoleMember {
+            key: ctx.accounts.admin.key(),
+            roles: ROLE_ALL,
+        }],
+        paused: 0,
         vault: ctx.accounts.vault.key(),
         vault_bump: ctx.bumps.vault,
         xdegen_mint: ctx.accounts.xdegen_mint.key(),
         claim_amount,
+        claim_cooldown: DEFAULT_CLAIM_COOLDOWN,
+        max_claim_per_wallet,
         total_trades: 0,
         total_buys: 0,
         total_sells: 0,
         total_claimed: 0,
+        treasury_balance: 0,
+        claim_pool: 0,
+        total_reserves: 0,
+        trade_tiers,
+        fee_vault: ctx.accounts.fee_vault.key(),
+        fee_vault_bump: ctx.bumps.fee_vault,
+        protocol_fee_bps: DEFAULT_PROTOCOL_FEE_BPS,
+        creator_fee_bps: DEFAULT_CREATOR_FEE_BPS,
+        referral_fee_bps: DEFAULT_REFERRAL_FEE_BPS,
+        protocol_fees: 0,
+        creator_fees: 0,
+        referral_fees: 0,
+        admin_signers: vec![ctx.accounts.admin.key()],
+        admin_threshold: 1,
+        proposal_count: 0,
+        withdraw_delay: DEFAULT_WITHDRAW_DELAY,
+        withdraw_cap,
+        pending_withdraw_delay: None,
+        pending_withdraw_cap: None,
+        pending_withdraw_limits_at: 0,
+        withdraw_epoch_start: 0,
+        withdrawn_in_epoch: 0,
+        withdrawal_count: 0,
         bump: ctx.bumps.config,
     });
+
+    emit_cpi!(Initialized {
+        admin: ctx.accounts.admin.key(),
+        xdegen_mint: ctx.accounts.xdegen_mint.key(),
+        vault: ctx.accounts.vault.key(),
+        claim_amount,
+        trade_tiers,
+        fee_vault: ctx.accounts.fee_vault.key(),
+        protocol_fee_bps: DEFAULT_PROTOCOL_FEE_BPS,
+        creator_fee_bps: DEFAULT_CREATOR_FEE_BPS,
+        referral_fee_bps: DEFAULT_REFERRAL_FEE_BPS,
+    });
     Ok(())
 }
@@ -10,8 +10,9 @@ use anchor_spl::token_interface::{
 };
 
 use session_keys::{Session, SessionToken};
-use crate::{ ALLOWED_AMOUNTS, Config, TokenRecord, error::ErrorCode };
+use crate::{ PAUSE_MINT_TOKEN, BondingCurve, Config, ReferralAccount, TokenRecord, error::ErrorCode, events::TokensMinted };
 
+#[event_cpi]
 #[derive(Accounts, Session)]
 pub struct MintToken<'info> {
     #[session(
@@ -25,16 +26,29 @@ pub struct MintToken<'info> {
     #[account(
         mut,
         has_one = xdegen_mint @ ErrorCode::InvalidMint,
+        has_one = fee_vault,
         seeds = [b"config"],
         bump = config.bump
     )]
     pub config: Account<'info, Config>,
-    #[account(mut)]
+    #[account(
+        mut,
+        mint::authority = mint_authority,
+        mint::token_program = mint_token_program
+    )]
     pub mint: InterfaceAccount<'info, Mint>,
+    /// CHECK: PDA that owns mint authority over the created token
+    #[account(
+        seeds = [b"mint_authority", mint.key().as_ref()],
+        bump = bonding_curve.mint_authority_bump
+    )]
+    pub mint_authority: UncheckedAccount<'info>,
     #[account(mut)]
     pub xdegen_mint: InterfaceAccount<'info, Mint>,
     #[account(mut)]
-    pub vault: InterfaceAccount<'info, TokenAccount>,
+    pub reserve: InterfaceAccount<'info, TokenAccount>,
+    #[account(mut)]
+    pub fee_vault: InterfaceAccount<'info, TokenAccount>,
     #[account(mut)]
     pub trader_xdegen_ata: InterfaceAccount<'info, TokenAccount>,
     #[account(mut)]
@@ -45,26 +59,85 @@ pub struct MintToken<'info> {
         bump
     )]
     pub token_record: Account<'info, TokenRecord>,
+    #[account(
+        mut,
+        has_one = mint @ ErrorCode::InvalidMint,
+        has_one = reserve,
+        seeds = [b"bonding_curve", mint.key().as_ref()],
+        bump = bonding_curve.bump
+    )]
+    pub bonding_curve: Account<'info, BondingCurve>,
+    /// CHECK: the trader's ReferralAccount, see Config::split_referral_fee
+    #[account(
+        mut,
+        seeds = [b"referral", trader.key().as_ref()],
+        bump
+    )]
+    pub trader_referral: UncheckedAccount<'info>,
+    #[account(
+        mut,
+        seeds = [b"referral", referrer_referral.owner.as_ref()],
+        bump = referrer_referral.bump
+    )]
+    pub referrer_referral: Option<Account<'info, ReferralAccount>>,
     
     pub token_program: Interface<'info, TokenInterface>,
+    pub mint_token_program: Interface<'info, TokenInterface>,
     pub system_program: Program<'info, System>,
 }
 
-pub fn mint_token_handler(ctx: Context<MintToken>, buy_amount: u64, mint_amount: u64) -> Result<()> {
+pub fn mint_token_handler(
+    ctx: Context<MintToken>, 
+    mint_amount: u64, 
+    max_xdegen_in: u64
+) -> Result<()> {
     let config = &mut ctx.accounts.config;
     let token_record = &mut ctx.accounts.token_record;
+    let bonding_curve = &mut ctx.accounts.bonding_curve;
+    config.require_not_paused(PAUSE_MINT_TOKEN)?;
 
+    require!(!bonding_curve.delisted, ErrorCode::TokenDelisted);
     require!(mint_amount > 0, ErrorCode::InvalidAmount);
+    require!(!token_record.fixed_supply, ErrorCode::FixedSupply);
+
+    let new_supply = ctx.accounts.mint.supply
+        .checked_add(mint_amount)
+        .ok_or(ErrorCode::MathOverflow)?;
+    require!(new_supply <= token_record.max_supply, ErrorCode::MaxSupplyExceeded);
+
+    // fees are charged on top of the curve cost so mint_amount stays exact
+    let buy_amount = bonding_curve.xdegen_in(mint_amount)?;
+    let (mut protocol_fee, creator_fee) = config.trade_fees(buy_amount)?;
+    let fees = protocol_fee + creator_fee;
+    let total_cost = buy_amount
+        .checked_add(fees)
+        .ok_or(ErrorCode::MathOverflow)?;
+
+    let mut trader_referral = ReferralAccount::load(&ctx.accounts.trader_referral)?;
+    let referral_fee = config.split_referral_fee(
+        trader_referral.as_mut(),
+        ctx.accounts.referrer_referral.as_deref_mut(),
+        protocol_fee
+    )?;
+    protocol_fee -= referral_fee;
+    if let Some(trader_referral) = trader_referral {
+        trader_referral.store(&ctx.accounts.trader_referral)?;
+    }
+
     require!(
-        buy_amount > 0 && ALLOWED_AMOUNTS.contains(&buy_amount), 
+        buy_amount > 0 && config.trade_tiers.within_cap(total_cost), 
         ErrorCode::InvalidAmount
     );
-    require!(ctx.accounts.trader_xdegen_ata.amount > 0, ErrorCode::InsufficientFunds);
+    require!(total_cost <= max_xdegen_in, ErrorCode::SlippageExceeded);
+    require!(
+        ctx.accounts.trader_xdegen_ata.amount >= total_cost, 
+        ErrorCode::InsufficientFunds
+    );
 
     msg!("
-        Transfer funds {} from trader to vault {} for minting token {}", 
+        Transfer funds {} from trader to reserve {} for minting token {}", 
         buy_amount,
-        ctx.accounts.vault.key(),
+        ctx.accounts.reserve.key(),
         ctx.accounts.xdegen_mint.key()
     );
     transfer_checked(
@@ -72,7 +145,7 @@ pub fn mint_token_handler(ctx: Context<MintToken>, buy_amount: u64, mint_amount:
             ctx.accounts.token_program.to_account_info(),
             TransferChecked {
                 from: ctx.accounts.trader_xdegen_ata.to_account_info(),
-                to: ctx.accounts.vault.to_account_info(),
+                to: ctx.accounts.reserve.to_account_info(),
                 mint: ctx.accounts.xdegen_mint.to_account_info(),
                 authority: ctx.accounts.trader.to_account_info(),
             }
@@ -81,33 +154,65 @@ pub fn mint_token_handler(ctx: Context<MintToken>, buy_amount: u64, mint_amount:
         ctx.accounts.xdegen_mint.decimals
     )?;
 
+    if fees > 0 {
+        msg!("Transfer trade fees {} to fee vault", fees);
+        transfer_checked(
+            CpiContext::new(
+                ctx.accounts.token_program.to_account_info(),
+                TransferChecked {
+                    from: ctx.accounts.trader_xdegen_ata.to_account_info(),
+                    to: ctx.accounts.fee_vault.to_account_info(),
+                    mint: ctx.accounts.xdegen_mint.to_account_info(),
+                    authority: ctx.accounts.trader.to_account_info(),
+                }
+            ),
+            fees,
+            ctx.accounts.xdegen_mint.decimals
+        )?;
+    }
+
     msg!(
         "Minting token {} to trader {}",
         ctx.accounts.mint.key(),
         ctx.accounts.trader.key()
     );
-    msg!(
-        "Mint token {} to trader {}", 
-        ctx.accounts.mint.key(), 
-        ctx.accounts.trader.key()
-    );
+    let mint = ctx.accounts.mint.key();
+    let signer_seeds: &[&[&[u8]]] = &[&[
+        b"mint_authority".as_ref(),
+        mint.as_ref(),
+        &[bonding_curve.mint_authority_bump],
+    ]];
+
     mint_to_checked(
-        CpiContext::new(
-            ctx.accounts.token_program.to_account_info(),
+        CpiContext::new_with_signer(
+            ctx.accounts.mint_token_program.to_account_info(),
             MintToChecked {
                 mint: ctx.accounts.mint.to_account_info(),
                 to: ctx.accounts.trader_mint_ata.to_account_info(),
-                authority: ctx.accounts.trader.to_account_info()
-            }
+                authority: ctx.accounts.mint_authority.to_account_info()
+            },
+            signer_seeds
         ), 
         mint_amount, 
         ctx.accounts.mint.decimals
     )?;
 
+    bonding_curve.record_buy(buy_amount, mint_amount)?;
+
     token_record.balance = token_record.balance
         .checked_add(mint_amount)
         .ok_or(ErrorCode::MathOverflow)?;
 
+    token_record.creator_fees = token_record.creator_fees
+        .checked_add(creator_fee)
+        .ok_or(ErrorCode::MathOverflow)?;
+
+    config.total_reserves = config.total_reserves
+        .checked_add(buy_amount)
+        .ok_or(ErrorCode::MathOverflow)?;
+
+    config.accrue_fees(protocol_fee, creator_fee, referral_fee)?;
+
     config.total_trades = config.total_trades
         .checked_add(1)
         .ok_or(ErrorCode::MathOverflow)?;
@@ -116,5 +221,19 @@ pub fn mint_token_handler(ctx: Context<MintToken>, buy_amount: u64, mint_amount:
         .checked_add(1)
         .ok_or(ErrorCode::MathOverflow)?;
 
+    emit_cpi!(TokensMinted {
+        trader: ctx.accounts.trader.key(),
+        mint: ctx.accounts.mint.key(),
+        xdegen_in: total_cost,
+        tokens_out: mint_amount,
+        protocol_fee,
+        creator_fee,
+        referrer: ctx.accounts.referrer_referral.as_ref().map(|r| r.owner),
+        referral_fee,
+        balance: token_record.balance,
+        total_trades: config.total_trades,
+        total_buys: config.total_buys,
+    });
+
     Ok(())
 }
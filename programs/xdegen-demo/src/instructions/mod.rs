@@ -4,8 +4,14 @@ pub use initialize::*;
 pub mod deposit;
 pub use deposit::*;
 
-pub mod withdraw;
-pub use withdraw::*;
+pub mod queue_withdrawal;
+pub use queue_withdrawal::*;
+
+pub mod execute_withdrawal;
+pub use execute_withdrawal::*;
+
+pub mod cancel_withdrawal;
+pub use cancel_withdrawal::*;
 
 pub mod buy;
 pub use buy::*;
@@ -17,4 +23,67 @@ pub mod claim;
 pub use claim::*;
 
 pub mod mint;
-pub use mint::*;
\ No newline at end of file
+pub use mint::*;
+
+pub mod update_trade_tiers;
+pub use update_trade_tiers::*;
+
+pub mod update_config;
+pub use update_config::*;
+
+pub mod propose_admin;
+pub use propose_admin::*;
+
+pub mod accept_admin;
+pub use accept_admin::*;
+
+pub mod cancel_admin_transfer;
+pub use cancel_admin_transfer::*;
+
+pub mod set_pause;
+pub use set_pause::*;
+
+pub mod claim_creator_fees;
+pub use claim_creator_fees::*;
+
+pub mod collect_protocol_fees;
+pub use collect_protocol_fees::*;
+
+pub mod create_referral_account;
+pub use create_referral_account::*;
+
+pub mod claim_referral_rewards;
+pub use claim_referral_rewards::*;
+
+pub mod update_token_metadata;
+pub use update_token_metadata::*;
+
+pub mod reserve_symbol;
+pub use reserve_symbol::*;
+
+pub mod release_symbol;
+pub use release_symbol::*;
+
+pub mod set_delisted;
+pub use set_delisted::*;
+
+pub mod close_token_record;
+pub use close_token_record::*;
+
+pub mod open_holder_position;
+pub use open_holder_position::*;
+
+pub mod create_admin_proposal;
+pub use create_admin_proposal::*;
+
+pub mod approve_admin_proposal;
+pub use approve_admin_proposal::*;
+
+pub mod execute_admin_proposal;
+pub use execute_admin_proposal::*;
+
+pub mod close_admin_proposal;
+pub use close_admin_proposal::*;
+
+pub mod set_roles;
+pub use set_roles::*;
\ No newline at end of file
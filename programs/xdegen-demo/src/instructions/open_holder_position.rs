@@ -0,0 +1,56 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
+
+use crate::{BondingCurve, HolderPosition, error::ErrorCode, events::HolderPositionOpened};
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct OpenHolderPosition<'info> {
+    #[account(mut)]
+    pub holder: Signer<'info>,
+    #[account(mint::token_program = mint_token_program)]
+    pub mint: InterfaceAccount<'info, Mint>,
+    #[account(
+        associated_token::mint = mint,
+        associated_token::authority = holder,
+        associated_token::token_program = mint_token_program,
+    )]
+    pub holder_mint_ata: InterfaceAccount<'info, TokenAccount>,
+    // only tokens created by buy have a curve to sell into
+    #[account(
+        has_one = mint @ ErrorCode::InvalidMint,
+        seeds = [b"bonding_curve", mint.key().as_ref()],
+        bump = bonding_curve.bump
+    )]
+    pub bonding_curve: Account<'info, BondingCurve>,
+    #[account(
+        init,
+        payer = holder,
+        space = 8 + HolderPosition::INIT_SPACE,
+        seeds = [b"holder_position", holder.key().as_ref(), mint.key().as_ref()],
+        bump
+    )]
+    pub holder_position: Account<'info, HolderPosition>,
+    pub mint_token_program: Interface<'info, TokenInterface>,
+    pub system_program: Program<'info, System>,
+}
+
+pub fn open_holder_position_handler(ctx: Context<OpenHolderPosition>) -> Result<()> {
+    require!(ctx.accounts.holder_mint_ata.amount > 0, ErrorCode::NoTokensHeld);
+
+    ctx.accounts.holder_position.set_inner(HolderPosition {
+        holder: ctx.accounts.holder.key(),
+        mint: ctx.accounts.mint.key(),
+        tokens_sold: 0,
+        xdegen_received: 0,
+        sell_count: 0,
+        opened_at: Clock::get()?.unix_timestamp,
+        bump: ctx.bumps.holder_position,
+    });
+
+    emit_cpi!(HolderPositionOpened {
+        holder: ctx.accounts.holder.key(),
+        mint: ctx.accounts.mint.key(),
+    });
+    Ok(())
+}
\ No newline at end of file
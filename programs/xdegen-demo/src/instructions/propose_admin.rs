@@ -0,0 +1,35 @@
+use anchor_lang::prelude::*;
+
+use crate::{error::ErrorCode, events::AdminTransferProposed, Config};
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct ProposeAdmin<'info> {
+    pub admin: Signer<'info>,
+    #[account(
+        mut,
+        has_one = admin @ ErrorCode::Unauthorized,
+        seeds = [b"config"],
+        bump = config.bump
+    )]
+    pub config: Account<'info, Config>,
+}
+
+pub fn propose_admin_handler(ctx: Context<ProposeAdmin>, new_admin: Pubkey) -> Result<()> {
+    let config = &mut ctx.accounts.config;
+
+    // the new admin takes over the old admin's signer seat, so it can't hold one already
+    require!(
+        new_admin != Pubkey::default() && !config.is_admin_signer(&new_admin) && new_admin != config.admin,
+        ErrorCode::InvalidAdmin
+    );
+
+    msg!("Admin transfer proposed to {}", new_admin);
+    config.pending_admin = Some(new_admin);
+
+    emit_cpi!(AdminTransferProposed {
+        admin: ctx.accounts.admin.key(),
+        pending_admin: Some(new_admin),
+    });
+    Ok(())
+}
\ No newline at end of file
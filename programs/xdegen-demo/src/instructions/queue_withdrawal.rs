@@ -0,0 +1,96 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{Mint, TokenAccount};
+
+use crate::{error::ErrorCode, events::WithdrawalQueued, Config, PendingWithdrawal, VaultBucket, ROLE_TREASURER};
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct QueueWithdrawal<'info> {
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    #[account(
+        mut,
+        constraint = config.has_role(&authority.key(), ROLE_TREASURER) @ ErrorCode::MissingRole,
+        seeds = [b"config"],
+        bump = config.bump
+    )]
+    pub config: Account<'info, Config>,
+    #[account(address = config.xdegen_mint @ ErrorCode::InvalidMint)]
+    pub xdegen_mint: InterfaceAccount<'info, Mint>,
+    // any XDEGEN token account, not only the admin's
+    #[account(token::mint = xdegen_mint)]
+    pub destination: InterfaceAccount<'info, TokenAccount>,
+    #[account(
+        init,
+        payer = authority,
+        space = 8 + PendingWithdrawal::INIT_SPACE,
+        seeds = [b"pending_withdrawal", config.withdrawal_count.to_le_bytes().as_ref()],
+        bump
+    )]
+    pub pending_withdrawal: Account<'info, PendingWithdrawal>,
+    pub system_program: Program<'info, System>
+}
+
+pub fn queue_withdrawal_handler(ctx: Context<QueueWithdrawal>, amount: u64, bucket: VaultBucket) -> Result<()> {
+    ctx.accounts.config.require_direct_authority(&ctx.accounts.authority.key())?;
+    let event = queue_pending_withdrawal(
+        &mut ctx.accounts.config,
+        &mut ctx.accounts.pending_withdrawal,
+        ctx.accounts.authority.key(),
+        ctx.accounts.destination.key(),
+        amount,
+        bucket,
+        ctx.bumps.pending_withdrawal
+    )?;
+    emit_cpi!(event);
+    Ok(())
+}
+
+// shared with execute_admin_proposal, returns the event for the caller to emit; balances,
+// the pause flag and the epoch cap are checked again when the withdrawal executes
+pub(crate) fn queue_pending_withdrawal(
+    config: &mut Config,
+    pending_withdrawal: &mut PendingWithdrawal,
+    payer: Pubkey,
+    destination: Pubkey,
+    amount: u64,
+    bucket: VaultBucket,
+    bump: u8
+) -> Result<WithdrawalQueued> {
+    let now = Clock::get()?.unix_timestamp;
+    config.apply_pending_withdraw_limits(now);
+
+    require!(amount > 0, ErrorCode::InvalidAmount);
+    require!(amount <= config.withdraw_cap, ErrorCode::WithdrawCapExceeded);
+    require!(amount <= config.bucket_balance(bucket), ErrorCode::InsufficientFunds);
+
+    let id = config.withdrawal_count;
+    config.withdrawal_count = id
+        .checked_add(1)
+        .ok_or(ErrorCode::MathOverflow)?;
+
+    let executable_at = now
+        .checked_add(config.withdraw_delay)
+        .ok_or(ErrorCode::MathOverflow)?;
+
+    msg!("Withdrawal {} queued until {}", id, executable_at);
+    *pending_withdrawal = PendingWithdrawal {
+        id,
+        payer,
+        destination,
+        amount,
+        bucket,
+        queued_at: now,
+        executable_at,
+        bump,
+    };
+
+    Ok(WithdrawalQueued {
+        admin: payer,
+        id,
+        destination,
+        amount,
+        bucket,
+        executable_at,
+    })
+}
\ No newline at end of file
@@ -0,0 +1,37 @@
+use anchor_lang::prelude::*;
+
+use crate::{Config, SymbolRecord, ROLE_MODERATOR, error::ErrorCode, events::SymbolReleased};
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct ReleaseSymbol<'info> {
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    #[account(
+        constraint = config.has_role(&authority.key(), ROLE_MODERATOR) @ ErrorCode::MissingRole,
+        seeds = [b"config"],
+        bump = config.bump
+    )]
+    pub config: Account<'info, Config>,
+    // works on reserved and taken symbols alike, a released token keeps its metadata
+    #[account(
+        mut,
+        close = authority,
+        seeds = [b"symbol", symbol_record.symbol.as_bytes()],
+        bump = symbol_record.bump
+    )]
+    pub symbol_record: Account<'info, SymbolRecord>,
+}
+
+pub fn release_symbol_handler(ctx: Context<ReleaseSymbol>) -> Result<()> {
+    ctx.accounts.config.require_direct_authority(&ctx.accounts.authority.key())?;
+    let symbol_record = &ctx.accounts.symbol_record;
+    msg!("Releasing symbol {}", symbol_record.symbol);
+
+    emit_cpi!(SymbolReleased {
+        admin: ctx.accounts.authority.key(),
+        symbol: symbol_record.symbol.clone(),
+        mint: symbol_record.mint,
+    });
+    Ok(())
+}
\ No newline at end of file
@@ -0,0 +1,40 @@
+use anchor_lang::prelude::*;
+
+use crate::{validate_symbol, Config, SymbolRecord, ROLE_MODERATOR, error::ErrorCode, events::SymbolReserved};
+
+#[event_cpi]
+#[derive(Accounts)]
+#[instruction(symbol: String)]
+pub struct ReserveSymbol<'info> {
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    #[account(
+        constraint = config.has_role(&authority.key(), ROLE_MODERATOR) @ ErrorCode::MissingRole,
+        seeds = [b"config"],
+        bump = config.bump
+    )]
+    pub config: Account<'info, Config>,
+    #[account(
+        init_if_needed,
+        payer = authority,
+        space = 8 + SymbolRecord::INIT_SPACE,
+        seeds = [b"symbol", SymbolRecord::seed(&symbol)],
+        bump
+    )]
+    pub symbol_record: Account<'info, SymbolRecord>,
+    pub system_program: Program<'info, System>,
+}
+
+pub fn reserve_symbol_handler(ctx: Context<ReserveSymbol>, symbol: String) -> Result<()> {
+    ctx.accounts.config.require_direct_authority(&ctx.accounts.authority.key())?;
+    validate_symbol(&symbol)?;
+
+    msg!("Reserving symbol {}", symbol);
+    ctx.accounts.symbol_record.register(symbol.clone(), None, ctx.bumps.symbol_record)?;
+
+    emit_cpi!(SymbolReserved {
+        admin: ctx.accounts.authority.key(),
+        symbol,
+    });
+    Ok(())
+}
\ No newline at end of file
@@ -12,86 +12,147 @@ use anchor_spl::{
     }
 };
 
-use session_keys::{Session, SessionToken};
-use crate::{ALLOWED_AMOUNTS, Config, TokenRecord, error::ErrorCode};
+use crate::{
+    PAUSE_SELL,
+    BondingCurve,
+    Config,
+    HolderPosition,
+    ReferralAccount,
+    TokenRecord,
+    error::ErrorCode,
+    events::TokensSold
+};
 
-#[derive(Accounts, Session)]
+#[event_cpi]
+#[derive(Accounts)]
 pub struct Sell<'info> {
-    #[session(
-       signer = trader,
-       authority = token_record.owner.key() 
-    )]
-    pub session_token: Option<Account<'info, SessionToken>>,
-
+    // no session keys: only the holder can burn from its token account and receive the proceeds
     #[account(mut)]
     pub trader: Signer<'info>,
     #[account(
         mut,
-        has_one = vault,
         has_one = xdegen_mint @ ErrorCode::InvalidMint,
+        has_one = fee_vault,
         seeds = [b"config"],
         bump = config.bump
     )]
     pub config: Account<'info, Config>,
     #[account(mut)]
-    pub vault: InterfaceAccount<'info, TokenAccount>,
+    pub reserve: InterfaceAccount<'info, TokenAccount>,
     #[account(mut)]
+    pub fee_vault: InterfaceAccount<'info, TokenAccount>,
+    #[account(
+        mut,
+        mint::token_program = mint_token_program
+    )]
     pub mint: InterfaceAccount<'info, Mint>,
+    // any token account of the trader holding the mint, sells are keyed on these holdings
     #[account(
         mut,
-        associated_token::mint = mint,
-        associated_token::authority = trader,
+        token::mint = mint,
+        token::authority = trader,
+        token::token_program = mint_token_program,
     )]
     pub trader_mint: InterfaceAccount<'info, TokenAccount>,
     #[account(mut)]
     pub xdegen_mint: InterfaceAccount<'info, Mint>,
     #[account(
-        mut,
+        init_if_needed,
+        payer = trader,
         associated_token::mint = xdegen_mint,
         associated_token::authority = trader,
     )]
     pub trader_xdegen_ata: InterfaceAccount<'info, TokenAccount>,
     #[account(
         mut,
-        seeds = [b"token_record", trader.key().as_ref(), mint.key().as_ref()],
+        has_one = mint @ ErrorCode::InvalidMint,
+        has_one = reserve,
+        seeds = [b"bonding_curve", mint.key().as_ref()],
+        bump = bonding_curve.bump
+    )]
+    pub bonding_curve: Account<'info, BondingCurve>,
+    /// CHECK: the creator's TokenRecord, credited with the creator fee in the handler; once the
+    /// creator closed it the creator fee goes to the protocol instead
+    #[account(
+        mut,
+        seeds = [b"token_record", bonding_curve.creator.as_ref(), mint.key().as_ref()],
+        bump
+    )]
+    pub token_record: UncheckedAccount<'info>,
+    // optional sell history of the trader, opened with open_holder_position
+    #[account(
+        mut,
+        has_one = mint @ ErrorCode::InvalidMint,
+        seeds = [b"holder_position", trader.key().as_ref(), mint.key().as_ref()],
+        bump = holder_position.bump
+    )]
+    pub holder_position: Option<Account<'info, HolderPosition>>,
+    /// CHECK: the trader's ReferralAccount, see Config::split_referral_fee
+    #[account(
+        mut,
+        seeds = [b"referral", trader.key().as_ref()],
         bump
     )]
-    pub token_record: Account<'info, TokenRecord>,
+    pub trader_referral: UncheckedAccount<'info>,
+    #[account(
+        mut,
+        seeds = [b"referral", referrer_referral.owner.as_ref()],
+        bump = referrer_referral.bump
+    )]
+    pub referrer_referral: Option<Account<'info, ReferralAccount>>,
 
     pub associated_token_program: Program<'info, AssociatedToken>,
     pub token_program: Interface<'info, TokenInterface>,
+    pub mint_token_program: Interface<'info, TokenInterface>,
     pub system_program: Program<'info, System>, 
 }
 
 pub fn sell_handler(
     ctx: Context<Sell>, 
-    sell_amount: u64, 
-    burn_amount: u64
+    burn_amount: u64, 
+    min_xdegen_out: u64
 ) -> Result<()> {
     let config = &mut ctx.accounts.config;
-    let token_record = &mut ctx.accounts.token_record;
+    let mut token_record = TokenRecord::load(&ctx.accounts.token_record)?;
+    let bonding_curve = &mut ctx.accounts.bonding_curve;
+    config.require_not_paused(PAUSE_SELL)?;
+
+    require!(burn_amount > 0, ErrorCode::InvalidAmount);
+    require!(ctx.accounts.trader_mint.amount >= burn_amount, ErrorCode::InsufficientFunds);
 
+    let sell_amount = bonding_curve.xdegen_out(burn_amount)?;
     require!(
-        sell_amount > 0 && ALLOWED_AMOUNTS.contains(&sell_amount), 
+        sell_amount > 0 && config.trade_tiers.within_cap(sell_amount), 
         ErrorCode::InvalidAmount
     );
-    require!(ctx.accounts.trader_xdegen_ata.amount > sell_amount, ErrorCode::InsufficientFunds);
-    require!(burn_amount > 0, ErrorCode::InvalidAmount);
 
-    msg!("Transfer buy token to vault");
-    transfer_checked(
-        CpiContext::new(
-            ctx.accounts.token_program.to_account_info(),
-            TransferChecked {
-                from: ctx.accounts.trader_xdegen_ata.to_account_info(),
-                to: ctx.accounts.vault.to_account_info(),
-                mint: ctx.accounts.xdegen_mint.to_account_info(),
-                authority: ctx.accounts.trader.to_account_info()
-            },
-        ),
-        sell_amount,
-        ctx.accounts.xdegen_mint.decimals
+    // fees come out of the payout, min_xdegen_out bounds what the trader receives
+    let (mut protocol_fee, mut creator_fee) = config.trade_fees(sell_amount)?;
+    let fees = protocol_fee + creator_fee;
+    let net_amount = sell_amount - fees;
+
+    let mut trader_referral = ReferralAccount::load(&ctx.accounts.trader_referral)?;
+    let referral_fee = config.split_referral_fee(
+        trader_referral.as_mut(),
+        ctx.accounts.referrer_referral.as_deref_mut(),
+        protocol_fee
     )?;
+    protocol_fee -= referral_fee;
+    if let Some(trader_referral) = trader_referral {
+        trader_referral.store(&ctx.accounts.trader_referral)?;
+    }
+
+    // nobody left to claim the creator's cut, it stays with the protocol
+    if token_record.is_none() {
+        protocol_fee += creator_fee;
+        creator_fee = 0;
+    }
+
+    require!(net_amount >= min_xdegen_out, ErrorCode::SlippageExceeded);
+    require!(
+        ctx.accounts.reserve.amount >= sell_amount, 
+        ErrorCode::InsufficientVaultFunds
+    );
 
     msg!(
         "Burn token supply {} from mint {}",
@@ -100,7 +161,7 @@ pub fn sell_handler(
     );
     burn(
         CpiContext::new(
-            ctx.accounts.token_program.to_account_info(),
+            ctx.accounts.mint_token_program.to_account_info(),
             Burn {
                 mint: ctx.accounts.mint.to_account_info(),
                 authority: ctx.accounts.trader.to_account_info(),
@@ -110,10 +171,70 @@ pub fn sell_handler(
         burn_amount
     )?;
 
-    token_record.balance = token_record.balance
-        .checked_sub(burn_amount)
+    msg!("Transfer sale proceeds from reserve");
+    let mint = ctx.accounts.mint.key();
+    let signer_seeds: &[&[&[u8]]] = &[&[
+        b"reserve".as_ref(),
+        mint.as_ref(),
+        &[bonding_curve.reserve_bump],
+    ]];
+
+    transfer_checked(
+        CpiContext::new_with_signer(
+            ctx.accounts.token_program.to_account_info(),
+            TransferChecked {
+                from: ctx.accounts.reserve.to_account_info(),
+                to: ctx.accounts.trader_xdegen_ata.to_account_info(),
+                mint: ctx.accounts.xdegen_mint.to_account_info(),
+                authority: ctx.accounts.reserve.to_account_info()
+            },
+            signer_seeds
+        ),
+        net_amount,
+        ctx.accounts.xdegen_mint.decimals
+    )?;
+
+    if fees > 0 {
+        msg!("Transfer trade fees {} to fee vault", fees);
+        transfer_checked(
+            CpiContext::new_with_signer(
+                ctx.accounts.token_program.to_account_info(),
+                TransferChecked {
+                    from: ctx.accounts.reserve.to_account_info(),
+                    to: ctx.accounts.fee_vault.to_account_info(),
+                    mint: ctx.accounts.xdegen_mint.to_account_info(),
+                    authority: ctx.accounts.reserve.to_account_info()
+                },
+                signer_seeds
+            ),
+            fees,
+            ctx.accounts.xdegen_mint.decimals
+        )?;
+    }
+
+    bonding_curve.record_sell(burn_amount, sell_amount)?;
+    if let Some(holder_position) = ctx.accounts.holder_position.as_mut() {
+        holder_position.record_sell(burn_amount, net_amount)?;
+    }
+
+    if let Some(token_record) = token_record.as_mut() {
+        // the creator may sell tokens received by transfer on top of what they bought
+        if token_record.owner == ctx.accounts.trader.key() {
+            token_record.balance = token_record.balance.saturating_sub(burn_amount);
+        }
+
+        token_record.creator_fees = token_record.creator_fees
+            .checked_add(creator_fee)
+            .ok_or(ErrorCode::MathOverflow)?;
+        token_record.store(&ctx.accounts.token_record)?;
+    }
+
+    config.total_reserves = config.total_reserves
+        .checked_sub(sell_amount)
         .ok_or(ErrorCode::MathOverflow)?;
 
+    config.accrue_fees(protocol_fee, creator_fee, referral_fee)?;
+
     config.total_trades = config.total_trades
         .checked_add(1)
         .ok_or(ErrorCode::MathOverflow)?;
@@ -122,5 +243,20 @@ pub fn sell_handler(
         .checked_add(1)
         .ok_or(ErrorCode::MathOverflow)?;
 
+    emit_cpi!(TokensSold {
+        trader: ctx.accounts.trader.key(),
+        mint: ctx.accounts.mint.key(),
+        tokens_in: burn_amount,
+        xdegen_out: net_amount,
+        protocol_fee,
+        creator_fee,
+        referrer: ctx.accounts.referrer_referral.as_ref().map(|r| r.owner),
+        referral_fee,
+        balance: token_record.as_ref().map_or(0, |token_record| token_record.balance),
+        token_account_balance: ctx.accounts.trader_mint.amount - burn_amount,
+        total_trades: config.total_trades,
+        total_sells: config.total_sells,
+    });
+
     Ok(())
 }
\ No newline at end of file
@@ -0,0 +1,35 @@
+use anchor_lang::prelude::*;
+
+use crate::{BondingCurve, Config, ROLE_MODERATOR, error::ErrorCode, events::DelistingUpdated};
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct SetDelisted<'info> {
+    pub authority: Signer<'info>,
+    #[account(
+        constraint = config.has_role(&authority.key(), ROLE_MODERATOR) @ ErrorCode::MissingRole,
+        seeds = [b"config"],
+        bump = config.bump
+    )]
+    pub config: Account<'info, Config>,
+    #[account(
+        mut,
+        seeds = [b"bonding_curve", bonding_curve.mint.as_ref()],
+        bump = bonding_curve.bump
+    )]
+    pub bonding_curve: Account<'info, BondingCurve>,
+}
+
+pub fn set_delisted_handler(ctx: Context<SetDelisted>, delisted: bool) -> Result<()> {
+    ctx.accounts.config.require_direct_authority(&ctx.accounts.authority.key())?;
+    let bonding_curve = &mut ctx.accounts.bonding_curve;
+    msg!("Mint {} delisted: {}", bonding_curve.mint, delisted);
+    bonding_curve.delisted = delisted;
+
+    emit_cpi!(DelistingUpdated {
+        admin: ctx.accounts.authority.key(),
+        mint: bonding_curve.mint,
+        delisted,
+    });
+    Ok(())
+}
\ No newline at end of file
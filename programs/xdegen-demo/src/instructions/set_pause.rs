@@ -0,0 +1,33 @@
+use anchor_lang::prelude::*;
+
+use crate::{error::ErrorCode, events::PauseUpdated, Config, ROLE_PAUSER};
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct SetPause<'info> {
+    pub authority: Signer<'info>,
+    #[account(
+        mut,
+        constraint = config.has_role(&authority.key(), ROLE_PAUSER) @ ErrorCode::MissingRole,
+        seeds = [b"config"],
+        bump = config.bump
+    )]
+    pub config: Account<'info, Config>,
+}
+
+pub fn set_pause_handler(ctx: Context<SetPause>, flags: u8, paused: bool) -> Result<()> {
+    let config = &mut ctx.accounts.config;
+    config.require_direct_authority(&ctx.accounts.authority.key())?;
+
+    let old_paused = config.set_paused(flags, paused)?;
+
+    msg!("Pause flags {:#08b}", config.paused);
+
+    let new_paused = config.paused;
+    emit_cpi!(PauseUpdated {
+        authority: ctx.accounts.authority.key(),
+        old_paused,
+        new_paused,
+    });
+    Ok(())
+}
\ No newline at end of file
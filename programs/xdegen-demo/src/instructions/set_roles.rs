@@ -0,0 +1,33 @@
+use anchor_lang::prelude::*;
+
+use crate::{error::ErrorCode, events::RolesUpdated, Config};
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct SetRoles<'info> {
+    pub admin: Signer<'info>,
+    #[account(
+        mut,
+        has_one = admin @ ErrorCode::Unauthorized,
+        seeds = [b"config"],
+        bump = config.bump
+    )]
+    pub config: Account<'info, Config>,
+}
+
+pub fn set_roles_handler(ctx: Context<SetRoles>, member: Pubkey, roles: u8, granted: bool) -> Result<()> {
+    let config = &mut ctx.accounts.config;
+    config.require_single_admin()?;
+
+    let old_roles = config.set_roles(member, roles, granted)?;
+
+    msg!("Roles of {} set to {:#06b}", member, config.roles_of(&member));
+
+    emit_cpi!(RolesUpdated {
+        admin: ctx.accounts.admin.key(),
+        member,
+        old_roles,
+        new_roles: config.roles_of(&member),
+    });
+    Ok(())
+}
\ No newline at end of file
@@ -0,0 +1,111 @@
+use anchor_lang::prelude::*;
+
+use crate::{error::ErrorCode, events::ConfigUpdated, Config, ROLE_OPERATOR};
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug, InitSpace)]
+pub struct UpdateConfigParams {
+    pub claim_amount: Option<u64>,
+    pub claim_cooldown: Option<i64>,
+    pub max_claim_per_wallet: Option<u64>,
+    pub protocol_fee_bps: Option<u16>,
+    pub creator_fee_bps: Option<u16>,
+    pub referral_fee_bps: Option<u16>,
+    pub withdraw_delay: Option<i64>,
+    pub withdraw_cap: Option<u64>,
+}
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct UpdateConfig<'info> {
+    pub authority: Signer<'info>,
+    #[account(
+        mut,
+        constraint = config.has_role(&authority.key(), ROLE_OPERATOR) @ ErrorCode::MissingRole,
+        seeds = [b"config"],
+        bump = config.bump
+    )]
+    pub config: Account<'info, Config>,
+}
+
+pub fn update_config_handler(ctx: Context<UpdateConfig>, params: UpdateConfigParams) -> Result<()> {
+    ctx.accounts.config.require_direct_authority(&ctx.accounts.authority.key())?;
+    let event = apply_config_update(&mut ctx.accounts.config, ctx.accounts.authority.key(), params)?;
+    emit_cpi!(event);
+    Ok(())
+}
+
+// shared with execute_admin_proposal, returns the event for the caller to emit
+pub(crate) fn apply_config_update(
+    config: &mut Config,
+    admin: Pubkey,
+    params: UpdateConfigParams
+) -> Result<ConfigUpdated> {
+    let old_claim_amount = config.claim_amount;
+    let old_claim_cooldown = config.claim_cooldown;
+    let old_max_claim_per_wallet = config.max_claim_per_wallet;
+    let old_protocol_fee_bps = config.protocol_fee_bps;
+    let old_creator_fee_bps = config.creator_fee_bps;
+    let old_referral_fee_bps = config.referral_fee_bps;
+    let old_withdraw_delay = config.withdraw_delay;
+    let old_withdraw_cap = config.withdraw_cap;
+
+    if let Some(claim_amount) = params.claim_amount {
+        require!(claim_amount > 0, ErrorCode::InvalidAmount);
+        config.claim_amount = claim_amount;
+    }
+
+    if let Some(claim_cooldown) = params.claim_cooldown {
+        require!(claim_cooldown >= 0, ErrorCode::InvalidAmount);
+        config.claim_cooldown = claim_cooldown;
+    }
+
+    if let Some(max_claim_per_wallet) = params.max_claim_per_wallet {
+        require!(max_claim_per_wallet > 0, ErrorCode::InvalidAmount);
+        config.max_claim_per_wallet = max_claim_per_wallet;
+    }
+
+    // checked on the result so a single claim can never be above the lifetime cap
+    require!(
+        config.claim_amount <= config.max_claim_per_wallet,
+        ErrorCode::InvalidAmount
+    );
+
+    if let Some(protocol_fee_bps) = params.protocol_fee_bps {
+        config.protocol_fee_bps = protocol_fee_bps;
+    }
+
+    if let Some(creator_fee_bps) = params.creator_fee_bps {
+        config.creator_fee_bps = creator_fee_bps;
+    }
+
+    if let Some(referral_fee_bps) = params.referral_fee_bps {
+        config.referral_fee_bps = referral_fee_bps;
+    }
+    config.validate_fees()?;
+
+    // only applies to withdrawals queued from now on
+    config.set_withdraw_limits(params.withdraw_delay, params.withdraw_cap, Clock::get()?.unix_timestamp)?;
+
+    Ok(ConfigUpdated {
+        admin,
+        old_claim_amount,
+        new_claim_amount: config.claim_amount,
+        old_claim_cooldown,
+        new_claim_cooldown: config.claim_cooldown,
+        old_max_claim_per_wallet,
+        new_max_claim_per_wallet: config.max_claim_per_wallet,
+        old_protocol_fee_bps,
+        new_protocol_fee_bps: config.protocol_fee_bps,
+        old_creator_fee_bps,
+        new_creator_fee_bps: config.creator_fee_bps,
+        old_referral_fee_bps,
+        new_referral_fee_bps: config.referral_fee_bps,
+        old_withdraw_delay,
+        new_withdraw_delay: config.withdraw_delay,
+        old_withdraw_cap,
+        new_withdraw_cap: config.withdraw_cap,
+        pending_withdraw_delay: config.pending_withdraw_delay,
+        pending_withdraw_cap: config.pending_withdraw_cap,
+        pending_withdraw_limits_at: config.pending_withdraw_limits_at,
+    })
+}
\ No newline at end of file
@@ -0,0 +1,287 @@
+use anchor_lang::prelude::*;
+use anchor_lang::system_program::{transfer, Transfer};
+use anchor_spl::{
+    metadata::{
+        mpl_token_metadata::types::DataV2,
+        update_metadata_accounts_v2,
+        Metadata,
+        UpdateMetadataAccountsV2,
+    },
+    token_interface::{
+        spl_pod::optional_keys::OptionalNonZeroPubkey,
+        spl_token_metadata_interface::state::{Field, TokenMetadata as Token2022Metadata},
+        token_metadata_update_authority,
+        token_metadata_update_field,
+        Mint,
+        TokenInterface,
+        TokenMetadataUpdateAuthority,
+        TokenMetadataUpdateField,
+    }
+};
+
+use session_keys::{Session, SessionToken};
+use crate::{
+    BondingCurve,
+    MetadataStandard,
+    SymbolRecord,
+    TokenRecord,
+    error::ErrorCode,
+    events::TokenMetadataUpdated
+};
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug)]
+pub struct UpdateTokenMetadataParams {
+    pub name: Option<String>,
+    pub symbol: Option<String>,
+    pub uri: Option<String>,
+    // makes the metadata immutable after applying the changes above
+    pub lock: bool,
+}
+
+impl UpdateTokenMetadataParams {
+    pub fn changes_symbol(&self, symbol: &str) -> bool {
+        self.symbol.as_deref().is_some_and(|new_symbol| new_symbol != symbol)
+    }
+}
+
+#[event_cpi]
+#[derive(Accounts, Session)]
+#[instruction(params: UpdateTokenMetadataParams)]
+pub struct UpdateTokenMetadata<'info> {
+    #[session(
+       signer = trader,
+       authority = token_record.owner.key() 
+    )]
+    pub session_token: Option<Account<'info, SessionToken>>,
+
+    #[account(mut)]
+    pub trader: Signer<'info>,
+    #[account(
+        mut,
+        has_one = mint @ ErrorCode::InvalidMint,
+        seeds = [b"token_record", token_record.owner.as_ref(), mint.key().as_ref()],
+        bump = token_record.bump
+    )]
+    pub token_record: Account<'info, TokenRecord>,
+    // Token-2022 metadata lives in the mint account itself
+    #[account(
+        mut,
+        mint::token_program = mint_token_program
+    )]
+    pub mint: InterfaceAccount<'info, Mint>,
+    /// CHECK: PDA that holds metadata update authority over the created token
+    #[account(
+        seeds = [b"mint_authority", mint.key().as_ref()],
+        bump = bonding_curve.mint_authority_bump
+    )]
+    pub mint_authority: UncheckedAccount<'info>,
+    #[account(
+        has_one = mint @ ErrorCode::InvalidMint,
+        seeds = [b"bonding_curve", mint.key().as_ref()],
+        bump = bonding_curve.bump
+    )]
+    pub bonding_curve: Account<'info, BondingCurve>,
+
+    // both only taken when params.symbol changes the symbol, the registry entry moves with it
+    #[account(
+        init_if_needed,
+        space = 8 + SymbolRecord::INIT_SPACE,
+        payer = trader,
+        constraint = params.changes_symbol(&token_record.metadata.symbol) @ ErrorCode::UnexpectedSymbolRecord,
+        seeds = [b"symbol", SymbolRecord::seed(params.symbol.as_deref().unwrap_or_default())],
+        bump
+    )]
+    pub new_symbol_record: Option<Box<Account<'info, SymbolRecord>>>,
+    /// CHECK: registry entry of the current symbol, closed in the handler only if this mint
+    /// still holds it, the admin may have released it already
+    #[account(
+        mut,
+        constraint = params.changes_symbol(&token_record.metadata.symbol) @ ErrorCode::UnexpectedSymbolRecord,
+        seeds = [b"symbol", SymbolRecord::seed(&token_record.metadata.symbol)],
+        bump
+    )]
+    pub old_symbol_record: Option<UncheckedAccount<'info>>,
+
+    /// CHECK: Metaplex mint metadata, only used with MetadataStandard::Metaplex
+    #[account(mut)]
+    pub metadata: Option<UncheckedAccount<'info>>,
+    pub token_metadata_program: Option<Program<'info, Metadata>>,
+    pub mint_token_program: Interface<'info, TokenInterface>,
+    pub system_program: Program<'info, System>,
+}
+
+pub fn update_token_metadata_handler(
+    ctx: Context<UpdateTokenMetadata>,
+    params: UpdateTokenMetadataParams
+) -> Result<()> {
+    let old_metadata = ctx.accounts.token_record.metadata.clone();
+    require!(old_metadata.is_mutable, ErrorCode::MetadataImmutable);
+
+    let mut metadata = old_metadata.clone();
+    if let Some(name) = params.name.clone() {
+        metadata.name = name;
+    }
+    if let Some(symbol) = params.symbol.clone() {
+        metadata.symbol = symbol;
+    }
+    if let Some(uri) = params.uri.clone() {
+        metadata.uri = uri;
+    }
+    metadata.validate()?;
+
+    let mint = ctx.accounts.mint.key();
+    if metadata.symbol != old_metadata.symbol {
+        let (Some(new_symbol_record), Some(old_symbol_record)) = (
+            ctx.accounts.new_symbol_record.as_deref_mut(),
+            ctx.accounts.old_symbol_record.as_ref()
+        ) else {
+            return err!(ErrorCode::MissingSymbolRecord);
+        };
+        new_symbol_record.register(
+            metadata.symbol.clone(),
+            Some(mint),
+            ctx.bumps.new_symbol_record.ok_or(ErrorCode::MissingSymbolRecord)?
+        )?;
+
+        let old_info = old_symbol_record.to_account_info();
+        let held = old_info.owner == &crate::ID
+            && SymbolRecord::try_deserialize(&mut &old_info.try_borrow_data()?[..])?.mint == Some(mint);
+        if held {
+            msg!("Releasing symbol {}", old_metadata.symbol);
+            let trader = ctx.accounts.trader.to_account_info();
+            **trader.try_borrow_mut_lamports()? += old_info.lamports();
+            **old_info.try_borrow_mut_lamports()? = 0;
+            old_info.assign(&System::id());
+            old_info.resize(0)?;
+        }
+    }
+
+    let signer_seeds: &[&[&[u8]]] = &[&[
+        b"mint_authority".as_ref(),
+        mint.as_ref(),
+        &[ctx.accounts.bonding_curve.mint_authority_bump],
+    ]];
+
+    msg!("Updating metadata for mint {}", mint);
+    match metadata.standard {
+        MetadataStandard::Metaplex => {
+            let (Some(metadata_account), Some(token_metadata_program)) = (
+                ctx.accounts.metadata.as_ref(),
+                ctx.accounts.token_metadata_program.as_ref()
+            ) else {
+                return err!(ErrorCode::MissingMetadataAccounts);
+            };
+
+            update_metadata_accounts_v2(
+                CpiContext::new_with_signer(
+                    token_metadata_program.to_account_info(),
+                    UpdateMetadataAccountsV2 {
+                        metadata: metadata_account.to_account_info(),
+                        update_authority: ctx.accounts.mint_authority.to_account_info()
+                    },
+                    signer_seeds
+                ),
+                None,
+                Some(DataV2 {
+                    name: metadata.name.clone(),
+                    symbol: metadata.symbol.clone(),
+                    uri: metadata.uri.clone(),
+                    seller_fee_basis_points: 0,
+                    creators: None,
+                    collection: None,
+                    uses: None,
+                }),
+                None,
+                params.lock.then_some(false)
+            )?;
+        }
+        MetadataStandard::Token2022 => {
+            // the token program resizes the mint, top up rent before a value grows
+            let old_len = Token2022Metadata {
+                name: old_metadata.name,
+                symbol: old_metadata.symbol,
+                uri: old_metadata.uri,
+                ..Default::default()
+            }.tlv_size_of()?;
+            let new_len = Token2022Metadata {
+                name: metadata.name.clone(),
+                symbol: metadata.symbol.clone(),
+                uri: metadata.uri.clone(),
+                ..Default::default()
+            }.tlv_size_of()?;
+
+            let mint_info = ctx.accounts.mint.to_account_info();
+            let data_len = (mint_info.data_len() + new_len).saturating_sub(old_len);
+            let top_up = Rent::get()?
+                .minimum_balance(data_len)
+                .saturating_sub(mint_info.lamports());
+            if top_up > 0 {
+                transfer(
+                    CpiContext::new(
+                        ctx.accounts.system_program.to_account_info(),
+                        Transfer {
+                            from: ctx.accounts.trader.to_account_info(),
+                            to: mint_info
+                        }
+                    ),
+                    top_up
+                )?;
+            }
+
+            let fields = [
+                (Field::Name, params.name),
+                (Field::Symbol, params.symbol),
+                (Field::Uri, params.uri),
+            ];
+            for (field, value) in fields {
+                let Some(value) = value else {
+                    continue;
+                };
+
+                token_metadata_update_field(
+                    CpiContext::new_with_signer(
+                        ctx.accounts.mint_token_program.to_account_info(),
+                        TokenMetadataUpdateField {
+                            program_id: ctx.accounts.mint_token_program.to_account_info(),
+                            metadata: ctx.accounts.mint.to_account_info(),
+                            update_authority: ctx.accounts.mint_authority.to_account_info()
+                        },
+                        signer_seeds
+                    ),
+                    field,
+                    value
+                )?;
+            }
+
+            if params.lock {
+                token_metadata_update_authority(
+                    CpiContext::new_with_signer(
+                        ctx.accounts.mint_token_program.to_account_info(),
+                        TokenMetadataUpdateAuthority {
+                            program_id: ctx.accounts.mint_token_program.to_account_info(),
+                            metadata: ctx.accounts.mint.to_account_info(),
+                            current_authority: ctx.accounts.mint_authority.to_account_info(),
+                            new_authority: ctx.accounts.mint_authority.to_account_info()
+                        },
+                        signer_seeds
+                    ),
+                    OptionalNonZeroPubkey::default()
+                )?;
+            }
+        }
+    }
+
+    metadata.is_mutable = !params.lock;
+    let token_record = &mut ctx.accounts.token_record;
+    token_record.metadata = metadata;
+
+    emit_cpi!(TokenMetadataUpdated {
+        owner: token_record.owner,
+        mint,
+        name: token_record.metadata.name.clone(),
+        symbol: token_record.metadata.symbol.clone(),
+        uri: token_record.metadata.uri.clone(),
+        is_mutable: token_record.metadata.is_mutable,
+    });
+    Ok(())
+}
\ No newline at end of file
@@ -0,0 +1,37 @@
+use anchor_lang::prelude::*;
+
+use crate::{error::ErrorCode, events::TradeTiersUpdated, Config, TradeTiers, ROLE_OPERATOR};
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct UpdateTradeTiers<'info> {
+    pub authority: Signer<'info>,
+    #[account(
+        mut,
+        constraint = config.has_role(&authority.key(), ROLE_OPERATOR) @ ErrorCode::MissingRole,
+        seeds = [b"config"],
+        bump = config.bump
+    )]
+    pub config: Account<'info, Config>,
+}
+
+pub fn update_trade_tiers_handler(ctx: Context<UpdateTradeTiers>, trade_tiers: TradeTiers) -> Result<()> {
+    ctx.accounts.config.require_direct_authority(&ctx.accounts.authority.key())?;
+    trade_tiers.validate()?;
+
+    msg!(
+        "Trade tiers {} to {} step {}",
+        trade_tiers.min_amount,
+        trade_tiers.max_amount,
+        trade_tiers.step
+    );
+    let old_trade_tiers = ctx.accounts.config.trade_tiers;
+    ctx.accounts.config.trade_tiers = trade_tiers;
+
+    emit_cpi!(TradeTiersUpdated {
+        admin: ctx.accounts.authority.key(),
+        old_trade_tiers,
+        new_trade_tiers: trade_tiers,
+    });
+    Ok(())
+}
\ No newline at end of file
@@ -1,5 +1,6 @@
 pub mod constants;
 pub mod error;
+pub mod events;
 pub mod instructions;
 pub mod state;
 
@@ -22,20 +23,85 @@ pub mod xdegen_demo {
         initialize_handler(ctx)
     }
 
-    pub fn deposit(ctx: Context<Deposit>, amount: u64) -> Result<()> {
-      deposit_handler(ctx, amount) 
+    pub fn deposit(ctx: Context<Deposit>, amount: u64, bucket: VaultBucket) -> Result<()> {
+      deposit_handler(ctx, amount, bucket) 
     }
 
-    pub fn withdraw(ctx: Context<Withdraw>, amount: u64) -> Result<()> {
-      withdraw_handler(ctx, amount)
+    pub fn queue_withdrawal(ctx: Context<QueueWithdrawal>, amount: u64, bucket: VaultBucket) -> Result<()> {
+      queue_withdrawal_handler(ctx, amount, bucket)
+    }
+
+    pub fn execute_withdrawal(ctx: Context<ExecuteWithdrawal>) -> Result<()> {
+      execute_withdrawal_handler(ctx)
+    }
+
+    pub fn cancel_withdrawal(ctx: Context<CancelWithdrawal>) -> Result<()> {
+      cancel_withdrawal_handler(ctx)
+    }
+
+    pub fn update_config(ctx: Context<UpdateConfig>, params: UpdateConfigParams) -> Result<()> {
+      update_config_handler(ctx, params)
+    }
+
+    pub fn propose_admin(ctx: Context<ProposeAdmin>, new_admin: Pubkey) -> Result<()> {
+      propose_admin_handler(ctx, new_admin)
+    }
+
+    pub fn accept_admin(ctx: Context<AcceptAdmin>) -> Result<()> {
+      accept_admin_handler(ctx)
+    }
+
+    pub fn cancel_admin_transfer(ctx: Context<CancelAdminTransfer>) -> Result<()> {
+      cancel_admin_transfer_handler(ctx)
+    }
+
+    pub fn set_pause(ctx: Context<SetPause>, flags: u8, paused: bool) -> Result<()> {
+      set_pause_handler(ctx, flags, paused)
+    }
+
+    pub fn set_roles(ctx: Context<SetRoles>, member: Pubkey, roles: u8, granted: bool) -> Result<()> {
+      set_roles_handler(ctx, member, roles, granted)
+    }
+
+    pub fn update_trade_tiers(ctx: Context<UpdateTradeTiers>, trade_tiers: TradeTiers) -> Result<()> {
+      update_trade_tiers_handler(ctx, trade_tiers)
+    }
+
+    pub fn create_admin_proposal(ctx: Context<CreateAdminProposal>, action: AdminAction) -> Result<()> {
+      create_admin_proposal_handler(ctx, action)
+    }
+
+    pub fn approve_admin_proposal(ctx: Context<ApproveAdminProposal>) -> Result<()> {
+      approve_admin_proposal_handler(ctx)
+    }
+
+    pub fn execute_admin_proposal(ctx: Context<ExecuteAdminProposal>) -> Result<()> {
+      execute_admin_proposal_handler(ctx)
+    }
+
+    pub fn close_admin_proposal(ctx: Context<CloseAdminProposal>) -> Result<()> {
+      close_admin_proposal_handler(ctx)
+    }
+
+    pub fn reserve_symbol(ctx: Context<ReserveSymbol>, symbol: String) -> Result<()> {
+      reserve_symbol_handler(ctx, symbol)
+    }
+
+    pub fn release_symbol(ctx: Context<ReleaseSymbol>) -> Result<()> {
+      release_symbol_handler(ctx)
+    }
+
+    pub fn set_delisted(ctx: Context<SetDelisted>, delisted: bool) -> Result<()> {
+      set_delisted_handler(ctx, delisted)
     }
 
     pub fn buy(
       ctx: Context<Buy>,
       data: TokenParams,
-      amount: u64
+      amount: u64,
+      min_tokens_out: u64
     ) -> Result<()> {
-      buy_handler(ctx, data, amount)
+      buy_handler(ctx, data, amount, min_tokens_out)
     }
 
     #[session_auth_or(
@@ -44,21 +110,52 @@ pub mod xdegen_demo {
     )]
     pub fn mint_token(
       ctx: Context<MintToken>, 
-      buy_amount: u64, 
-      mint_amount: u64
+      mint_amount: u64, 
+      max_xdegen_in: u64
     ) -> Result<()> {
-      mint_token_handler(ctx, buy_amount, mint_amount)
+      mint_token_handler(ctx, mint_amount, max_xdegen_in)
+    }
+
+    pub fn open_holder_position(ctx: Context<OpenHolderPosition>) -> Result<()> {
+      open_holder_position_handler(ctx)
+    }
+
+    pub fn sell(ctx: Context<Sell>, burn_amount: u64, min_xdegen_out: u64) -> Result<()> {
+      sell_handler(ctx, burn_amount, min_xdegen_out)
     }
 
     #[session_auth_or(
       ctx.accounts.token_record.owner == ctx.accounts.trader.key(),
       ErrorCode::Unauthorized
     )]
-    pub fn sell(ctx: Context<Sell>, sell_amount: u64, burn_amount: u64) -> Result<()> {
-      sell_handler(ctx, sell_amount, burn_amount)
+    pub fn update_token_metadata(
+      ctx: Context<UpdateTokenMetadata>,
+      params: UpdateTokenMetadataParams
+    ) -> Result<()> {
+      update_token_metadata_handler(ctx, params)
+    }
+
+    pub fn close_token_record(ctx: Context<CloseTokenRecord>) -> Result<()> {
+      close_token_record_handler(ctx)
     }
 
     pub fn claim(ctx: Context<Claim>) -> Result<()> {
       claim_handler(ctx)
     }
+
+    pub fn claim_creator_fees(ctx: Context<ClaimCreatorFees>) -> Result<()> {
+      claim_creator_fees_handler(ctx)
+    }
+
+    pub fn collect_protocol_fees(ctx: Context<CollectProtocolFees>) -> Result<()> {
+      collect_protocol_fees_handler(ctx)
+    }
+
+    pub fn create_referral_account(ctx: Context<CreateReferralAccount>) -> Result<()> {
+      create_referral_account_handler(ctx)
+    }
+
+    pub fn claim_referral_rewards(ctx: Context<ClaimReferralRewards>) -> Result<()> {
+      claim_referral_rewards_handler(ctx)
+    }
 }
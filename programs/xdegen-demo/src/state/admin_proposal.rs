@@ -0,0 +1,47 @@
+use anchor_lang::prelude::*;
+
+use crate::{TradeTiers, UpdateConfigParams, VaultBucket, error::ErrorCode};
+
+// one per proposal at ["admin_proposal", id], ids come from Config.proposal_count
+#[account]
+#[derive(InitSpace)]
+pub struct AdminProposal {
+    pub id: u64,
+    pub proposer: Pubkey,
+    pub action: AdminAction,
+    // admin signers that approved, the proposer included
+    #[max_len(10)]
+    pub approvals: Vec<Pubkey>,
+    pub created_at: i64,
+    pub expires_at: i64,
+    pub executed: bool,
+    pub bump: u8
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug, InitSpace)]
+pub enum AdminAction {
+    // queues a PendingWithdrawal out of the vault to the `destination` token account
+    Withdraw { amount: u64, bucket: VaultBucket, destination: Pubkey },
+    UpdateConfig { params: UpdateConfigParams },
+    UpdateTradeTiers { trade_tiers: TradeTiers },
+    SetPause { flags: u8, paused: bool },
+    SetRoles { member: Pubkey, roles: u8, granted: bool },
+    SetSigners {
+        #[max_len(10)]
+        signers: Vec<Pubkey>,
+        threshold: u8
+    },
+}
+
+impl AdminProposal {
+    pub fn require_open(&self, now: i64) -> Result<()> {
+        require!(!self.executed, ErrorCode::ProposalExecuted);
+        require!(now < self.expires_at, ErrorCode::ProposalExpired);
+        Ok(())
+    }
+
+    /// Approvals from keys still in `signers`, so removed signers stop counting.
+    pub fn approval_count(&self, signers: &[Pubkey]) -> usize {
+        self.approvals.iter().filter(|key| signers.contains(key)).count()
+    }
+}
\ No newline at end of file
@@ -0,0 +1,175 @@
+use anchor_lang::prelude::*;
+
+use crate::error::ErrorCode;
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq, Eq, InitSpace)]
+pub enum CurveType {
+    // price = x / t over the live reserves, x * t held constant
+    ConstantProduct,
+    // price = (vx / vt) * (1 + sold / vt), doubling every vt tokens sold
+    Linear,
+}
+
+#[account]
+#[derive(InitSpace)]
+pub struct BondingCurve {
+    pub mint: Pubkey,
+    // wallet that ran buy, owner of the mint's TokenRecord
+    pub creator: Pubkey,
+    pub curve_type: CurveType,
+    pub reserve: Pubkey,
+    pub reserve_bump: u8,
+    pub mint_authority_bump: u8,
+    pub virtual_xdegen_reserves: u64,
+    pub virtual_token_reserves: u64,
+    pub real_xdegen_reserves: u64,
+    pub tokens_sold: u64,
+    // set by a moderator with set_delisted; stops mint_token while holders can still sell
+    pub delisted: bool,
+    pub bump: u8,
+}
+
+impl BondingCurve {
+    /// Tokens minted for `xdegen_in`, rounded down.
+    pub fn tokens_out(&self, xdegen_in: u64) -> Result<u64> {
+        let dx = xdegen_in as u128;
+        let out = match self.curve_type {
+            CurveType::ConstantProduct => {
+                let (x, t) = self.live_reserves()?;
+                mul_div_floor(t, dx, x.checked_add(dx).ok_or(ErrorCode::MathOverflow)?)?
+            }
+            CurveType::Linear => {
+                let vt = self.virtual_token_reserves as u128;
+                let a = vt
+                    .checked_add(self.tokens_sold as u128)
+                    .ok_or(ErrorCode::MathOverflow)?;
+                let term = mul_div_floor(vt, dx, self.virtual_xdegen_reserves as u128)?
+                    .checked_mul(2 * vt)
+                    .ok_or(ErrorCode::MathOverflow)?;
+                let disc = a
+                    .checked_mul(a)
+                    .and_then(|a2| a2.checked_add(term))
+                    .ok_or(ErrorCode::MathOverflow)?;
+                isqrt(disc) - a
+            }
+        };
+        u64::try_from(out).map_err(|_| error!(ErrorCode::MathOverflow))
+    }
+
+    /// XDEGEN required to mint exactly `tokens`, rounded up.
+    pub fn xdegen_in(&self, tokens: u64) -> Result<u64> {
+        let dy = tokens as u128;
+        let cost = match self.curve_type {
+            CurveType::ConstantProduct => {
+                let (x, t) = self.live_reserves()?;
+                require!(dy < t, ErrorCode::InvalidAmount);
+                mul_div_ceil(x, dy, t - dy)?
+            }
+            CurveType::Linear => {
+                let s0 = self.tokens_sold as u128;
+                let s1 = s0.checked_add(dy).ok_or(ErrorCode::MathOverflow)?;
+                self.linear_area(s0, s1, true)?
+            }
+        };
+        u64::try_from(cost).map_err(|_| error!(ErrorCode::MathOverflow))
+    }
+
+    /// XDEGEN returned for burning `tokens`, rounded down.
+    pub fn xdegen_out(&self, tokens: u64) -> Result<u64> {
+        require!(tokens <= self.tokens_sold, ErrorCode::InvalidAmount);
+
+        let dy = tokens as u128;
+        let payout = match self.curve_type {
+            CurveType::ConstantProduct => {
+                let (x, t) = self.live_reserves()?;
+                mul_div_floor(x, dy, t + dy)?
+            }
+            CurveType::Linear => {
+                let s1 = self.tokens_sold as u128;
+                self.linear_area(s1 - dy, s1, false)?
+            }
+        };
+        let payout = u64::try_from(payout).map_err(|_| error!(ErrorCode::MathOverflow))?;
+
+        Ok(payout.min(self.real_xdegen_reserves))
+    }
+
+    pub fn record_buy(&mut self, xdegen_in: u64, tokens_out: u64) -> Result<()> {
+        self.real_xdegen_reserves = self.real_xdegen_reserves
+            .checked_add(xdegen_in)
+            .ok_or(ErrorCode::MathOverflow)?;
+
+        self.tokens_sold = self.tokens_sold
+            .checked_add(tokens_out)
+            .ok_or(ErrorCode::MathOverflow)?;
+
+        Ok(())
+    }
+
+    pub fn record_sell(&mut self, tokens_in: u64, xdegen_out: u64) -> Result<()> {
+        self.real_xdegen_reserves = self.real_xdegen_reserves
+            .checked_sub(xdegen_out)
+            .ok_or(ErrorCode::MathOverflow)?;
+
+        self.tokens_sold = self.tokens_sold
+            .checked_sub(tokens_in)
+            .ok_or(ErrorCode::MathOverflow)?;
+
+        Ok(())
+    }
+
+    fn live_reserves(&self) -> Result<(u128, u128)> {
+        let x = (self.virtual_xdegen_reserves as u128)
+            .checked_add(self.real_xdegen_reserves as u128)
+            .ok_or(ErrorCode::MathOverflow)?;
+        let t = (self.virtual_token_reserves as u128)
+            .checked_sub(self.tokens_sold as u128)
+            .ok_or(ErrorCode::MathOverflow)?;
+
+        Ok((x, t))
+    }
+
+    // vx * (s1 - s0) * (2vt + s0 + s1) / 2vt^2, divided in two steps to stay in u128
+    fn linear_area(&self, s0: u128, s1: u128, round_up: bool) -> Result<u128> {
+        let vx = self.virtual_xdegen_reserves as u128;
+        let vt = self.virtual_token_reserves as u128;
+        let width = (s1 - s0)
+            .checked_mul(2 * vt + s0 + s1)
+            .ok_or(ErrorCode::MathOverflow)?;
+
+        if round_up {
+            mul_div_ceil(div_ceil(width, 2 * vt)?, vx, vt)
+        } else {
+            mul_div_floor(width / (2 * vt), vx, vt)
+        }
+    }
+}
+
+fn mul_div_floor(a: u128, b: u128, d: u128) -> Result<u128> {
+    require!(d > 0, ErrorCode::MathOverflow);
+    a.checked_mul(b)
+        .map(|n| n / d)
+        .ok_or(ErrorCode::MathOverflow.into())
+}
+
+fn mul_div_ceil(a: u128, b: u128, d: u128) -> Result<u128> {
+    div_ceil(a.checked_mul(b).ok_or(ErrorCode::MathOverflow)?, d)
+}
+
+fn div_ceil(n: u128, d: u128) -> Result<u128> {
+    require!(d > 0, ErrorCode::MathOverflow);
+    Ok(n.div_ceil(d))
+}
+
+fn isqrt(n: u128) -> u128 {
+    if n < 2 {
+        return n;
+    }
+    let mut x = n;
+    let mut y = n / 2 + 1;
+    while y < x {
+        x = y;
+        y = (x + n / x) / 2;
+    }
+    x
+}
@@ -0,0 +1,11 @@
+use anchor_lang::prelude::*;
+
+#[account]
+#[derive(InitSpace)]
+pub struct ClaimRecord {
+    pub claimer: Pubkey,
+    pub last_claimed_at: i64,
+    pub total_claimed: u64,
+    pub claim_count: u64,
+    pub bump: u8
+}
\ No newline at end of file
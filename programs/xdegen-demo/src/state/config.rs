@@ -1,38 +1,458 @@
 use anchor_lang::prelude::*;
 
+use crate::{
+    BPS_DENOMINATOR, MAX_TOTAL_FEE_BPS, MAX_DECIMALS, MIN_TOKEN_SUPPLY, MAX_TOKEN_SUPPLY,
+    MAX_ADMIN_SIGNERS, MAX_ROLE_MEMBERS, MAX_WITHDRAW_DELAY, MIN_WITHDRAW_DELAY, PAUSE_ALL, ROLE_ALL, ROLE_TREASURER, WITHDRAW_EPOCH,
+    CurveType, MetadataStandard, ReferralAccount, TokenMetadata, error::ErrorCode
+};
+
 #[account]
 #[derive(InitSpace)]
 pub struct Config {
     pub admin: Pubkey,
+    pub pending_admin: Option<Pubkey>,
+    // treasurer, operator, pauser and moderator grants, independent of the admin key
+    #[max_len(10)]
+    pub role_members: Vec<RoleMember>,
+    pub paused: u8,
     pub vault: Pubkey,
     pub vault_bump: u8,
     pub xdegen_mint: Pubkey,
     pub total_trades: u64,
     pub claim_amount: u64,
+    pub claim_cooldown: i64,
+    pub max_claim_per_wallet: u64,
     pub total_buys: u64,
     pub total_sells: u64,
     pub total_claimed: u64,
+    // vault balance split between admin treasury and faucet; per-token
+    // reserves live in their own accounts and are only summed here
+    pub treasury_balance: u64,
+    pub claim_pool: u64,
+    pub total_reserves: u64,
+    pub trade_tiers: TradeTiers,
+    // trade fees sit in the fee vault until collected; creator_fees and
+    // referral_fees are the sums of every TokenRecord.creator_fees and
+    // ReferralAccount.rewards, referral_fee_bps is taken out of the protocol fee
+    pub fee_vault: Pubkey,
+    pub fee_vault_bump: u8,
+    pub protocol_fee_bps: u16,
+    pub creator_fee_bps: u16,
+    pub referral_fee_bps: u16,
+    pub protocol_fees: u64,
+    pub creator_fees: u64,
+    pub referral_fees: u64,
+    // m-of-n admin set approving AdminProposals; while the threshold is 1 the admin
+    // can still act directly, above that admin actions only run through proposals
+    #[max_len(10)]
+    pub admin_signers: Vec<Pubkey>,
+    pub admin_threshold: u8,
+    pub proposal_count: u64,
+    // vault withdrawals wait withdraw_delay seconds in a PendingWithdrawal, and at most
+    // withdraw_cap leaves the vault in the WITHDRAW_EPOCH window opened at withdraw_epoch_start
+    pub withdraw_delay: i64,
+    pub withdraw_cap: u64,
+    // a shorter delay or higher cap only takes over at pending_withdraw_limits_at, one full
+    // withdraw_delay after update_config asked for it
+    pub pending_withdraw_delay: Option<i64>,
+    pub pending_withdraw_cap: Option<u64>,
+    pub pending_withdraw_limits_at: i64,
+    pub withdraw_epoch_start: i64,
+    pub withdrawn_in_epoch: u64,
+    pub withdrawal_count: u64,
     pub bump: u8,
 }
 
+impl Config {
+    pub fn require_not_paused(&self, flag: u8) -> Result<()> {
+        require!(self.paused & flag == 0, ErrorCode::ProgramPaused);
+        Ok(())
+    }
+
+    pub fn has_role(&self, key: &Pubkey, role: u8) -> bool {
+        self.role_members
+            .iter()
+            .any(|member| member.key == *key && member.roles & role == role)
+    }
+
+    /// Grants or revokes `roles` for `key`, returning its old roles. Members left
+    /// without any role are dropped from the list.
+    pub fn set_roles(&mut self, key: Pubkey, roles: u8, granted: bool) -> Result<u8> {
+        require!(
+            roles != 0 && roles & !ROLE_ALL == 0 && key != Pubkey::default(),
+            ErrorCode::InvalidRoles
+        );
+
+        let Some(index) = self.role_members.iter().position(|member| member.key == key) else {
+            if granted {
+                require!(self.role_members.len() < MAX_ROLE_MEMBERS, ErrorCode::InvalidRoles);
+                self.role_members.push(RoleMember { key, roles });
+            }
+            return Ok(0);
+        };
+
+        let member = &mut self.role_members[index];
+        let old_roles = member.roles;
+        if granted {
+            member.roles |= roles;
+        } else {
+            member.roles &= !roles;
+        }
+        if member.roles == 0 {
+            self.role_members.remove(index);
+        }
+        Ok(old_roles)
+    }
+
+    pub fn roles_of(&self, key: &Pubkey) -> u8 {
+        self.role_members
+            .iter()
+            .find(|member| member.key == *key)
+            .map_or(0, |member| member.roles)
+    }
+
+    pub fn require_single_admin(&self) -> Result<()> {
+        require!(self.admin_threshold <= 1, ErrorCode::MultisigRequired);
+        Ok(())
+    }
+
+    /// Role holders act directly, but the admin and admin signers only while the threshold is 1,
+    /// so the roles `initialize` gave the admin can't bypass the multisig.
+    pub fn require_direct_authority(&self, key: &Pubkey) -> Result<()> {
+        if *key == self.admin || self.is_admin_signer(key) {
+            self.require_single_admin()?;
+        }
+        Ok(())
+    }
+
+    pub fn is_admin_signer(&self, key: &Pubkey) -> bool {
+        self.admin_signers.contains(key)
+    }
+
+    /// Makes `new_admin` the admin, handing it the old admin's signer seat and roles.
+    pub fn transfer_admin(&mut self, new_admin: Pubkey) -> Result<()> {
+        require!(!self.is_admin_signer(&new_admin), ErrorCode::InvalidAdmin);
+
+        let old_admin = self.admin;
+        if let Some(signer) = self.admin_signers.iter_mut().find(|key| **key == old_admin) {
+            *signer = new_admin;
+        }
+        let roles = self.roles_of(&old_admin);
+        if roles != 0 {
+            self.set_roles(old_admin, roles, false)?;
+            self.set_roles(new_admin, roles, true)?;
+        }
+        self.admin = new_admin;
+        Ok(())
+    }
+
+    /// Treasurers, or any member of the admin set, may run or cancel queued withdrawals.
+    pub fn can_manage_withdrawals(&self, key: &Pubkey) -> bool {
+        self.has_role(key, ROLE_TREASURER) || self.is_admin_signer(key)
+    }
+
+    pub fn set_admin_signers(&mut self, signers: Vec<Pubkey>, threshold: u8) -> Result<()> {
+        validate_admin_signers(&signers, threshold)?;
+
+        self.admin_signers = signers;
+        self.admin_threshold = threshold;
+        Ok(())
+    }
+
+    /// Sets or clears `flags`, returning the old bitmask.
+    pub fn set_paused(&mut self, flags: u8, paused: bool) -> Result<u8> {
+        require!(flags != 0 && flags & !PAUSE_ALL == 0, ErrorCode::InvalidPauseFlags);
+
+        let old_paused = self.paused;
+        if paused {
+            self.paused |= flags;
+        } else {
+            self.paused &= !flags;
+        }
+        Ok(old_paused)
+    }
+
+    pub fn credit(&mut self, bucket: VaultBucket, amount: u64) -> Result<()> {
+        let balance = self.bucket_mut(bucket);
+        *balance = balance
+            .checked_add(amount)
+            .ok_or(ErrorCode::MathOverflow)?;
+
+        Ok(())
+    }
+
+    pub fn debit(&mut self, bucket: VaultBucket, amount: u64) -> Result<()> {
+        let balance = self.bucket_mut(bucket);
+        *balance = balance
+            .checked_sub(amount)
+            .ok_or(ErrorCode::InsufficientFunds)?;
+
+        Ok(())
+    }
+
+    /// Moves the pending withdraw delay and cap in once `pending_withdraw_limits_at` has passed.
+    pub fn apply_pending_withdraw_limits(&mut self, now: i64) {
+        if now < self.pending_withdraw_limits_at {
+            return;
+        }
+        if let Some(withdraw_delay) = self.pending_withdraw_delay.take() {
+            self.withdraw_delay = withdraw_delay;
+        }
+        if let Some(withdraw_cap) = self.pending_withdraw_cap.take() {
+            self.withdraw_cap = withdraw_cap;
+        }
+    }
+
+    /// Applies a longer delay or lower cap at once and schedules a shorter delay or higher cap
+    /// for `now + withdraw_delay`, leaving watchers a full delay to react before looser limits apply.
+    pub fn set_withdraw_limits(
+        &mut self,
+        withdraw_delay: Option<i64>,
+        withdraw_cap: Option<u64>,
+        now: i64
+    ) -> Result<()> {
+        self.apply_pending_withdraw_limits(now);
+        let unlock_at = now
+            .checked_add(self.withdraw_delay)
+            .ok_or(ErrorCode::MathOverflow)?;
+
+        if let Some(withdraw_delay) = withdraw_delay {
+            require!(
+                (MIN_WITHDRAW_DELAY..=MAX_WITHDRAW_DELAY).contains(&withdraw_delay),
+                ErrorCode::InvalidAmount
+            );
+            if withdraw_delay >= self.withdraw_delay {
+                self.withdraw_delay = withdraw_delay;
+                self.pending_withdraw_delay = None;
+            } else {
+                self.pending_withdraw_delay = Some(withdraw_delay);
+                self.pending_withdraw_limits_at = unlock_at;
+            }
+        }
+
+        if let Some(withdraw_cap) = withdraw_cap {
+            require!(withdraw_cap > 0, ErrorCode::InvalidAmount);
+            if withdraw_cap <= self.withdraw_cap {
+                self.withdraw_cap = withdraw_cap;
+                self.pending_withdraw_cap = None;
+            } else {
+                self.pending_withdraw_cap = Some(withdraw_cap);
+                self.pending_withdraw_limits_at = unlock_at;
+            }
+        }
+        Ok(())
+    }
+
+    pub fn bucket_balance(&self, bucket: VaultBucket) -> u64 {
+        match bucket {
+            VaultBucket::Treasury => self.treasury_balance,
+            VaultBucket::ClaimPool => self.claim_pool,
+        }
+    }
+
+    /// Counts `amount` against the withdraw cap, opening a new epoch once the last one ran out.
+    pub fn record_withdrawal(&mut self, amount: u64, now: i64) -> Result<()> {
+        if now >= self.withdraw_epoch_start.saturating_add(WITHDRAW_EPOCH) {
+            self.withdraw_epoch_start = now;
+            self.withdrawn_in_epoch = 0;
+        }
+
+        let withdrawn = self.withdrawn_in_epoch
+            .checked_add(amount)
+            .ok_or(ErrorCode::MathOverflow)?;
+        require!(withdrawn <= self.withdraw_cap, ErrorCode::WithdrawCapExceeded);
+
+        self.withdrawn_in_epoch = withdrawn;
+        Ok(())
+    }
+
+    pub fn validate_fees(&self) -> Result<()> {
+        let total_bps = self.protocol_fee_bps as u32 + self.creator_fee_bps as u32;
+        require!(total_bps <= MAX_TOTAL_FEE_BPS as u32, ErrorCode::InvalidFee);
+        require!(
+            self.referral_fee_bps as u64 <= BPS_DENOMINATOR,
+            ErrorCode::InvalidFee
+        );
+        Ok(())
+    }
+
+    /// Protocol and creator fee on an XDEGEN amount, each rounded down.
+    pub fn trade_fees(&self, amount: u64) -> Result<(u64, u64)> {
+        Ok((
+            fee_of(amount, self.protocol_fee_bps)?,
+            fee_of(amount, self.creator_fee_bps)?,
+        ))
+    }
+
+    /// Referrer share of a protocol fee, rounded down.
+    pub fn referral_fee(&self, protocol_fee: u64) -> Result<u64> {
+        fee_of(protocol_fee, self.referral_fee_bps)
+    }
+
+    /// Links the trader to `referrer_referral` and accrues the referrer's cut of `protocol_fee`
+    /// to it, returning that cut for the caller to take out of the protocol fee.
+    ///
+    /// Trades always pass the trader's `["referral", trader]` PDA as an unchecked account, loaded
+    /// with `ReferralAccount::load` and `None` until created, so a trader already bound to a
+    /// referrer can't dodge the split by leaving its own account out. Such a trader must pass the
+    /// referrer's account too, or the trade fails with `MissingReferrer`.
+    pub fn split_referral_fee(
+        &self,
+        trader_referral: Option<&mut ReferralAccount>,
+        referrer_referral: Option<&mut ReferralAccount>,
+        protocol_fee: u64
+    ) -> Result<u64> {
+        match (trader_referral, referrer_referral) {
+            (Some(trader_referral), Some(referrer_referral)) => {
+                trader_referral.link(referrer_referral)?;
+
+                let referral_fee = self.referral_fee(protocol_fee)?;
+                referrer_referral.accrue(referral_fee)?;
+                Ok(referral_fee)
+            }
+            (Some(trader_referral), None) => {
+                require!(trader_referral.referrer.is_none(), ErrorCode::MissingReferrer);
+                Ok(0)
+            }
+            (None, Some(_)) => err!(ErrorCode::InvalidReferrer),
+            (None, None) => Ok(0),
+        }
+    }
+
+    pub fn accrue_fees(
+        &mut self,
+        protocol_fee: u64,
+        creator_fee: u64,
+        referral_fee: u64
+    ) -> Result<()> {
+        self.protocol_fees = self.protocol_fees
+            .checked_add(protocol_fee)
+            .ok_or(ErrorCode::MathOverflow)?;
+
+        self.creator_fees = self.creator_fees
+            .checked_add(creator_fee)
+            .ok_or(ErrorCode::MathOverflow)?;
+
+        self.referral_fees = self.referral_fees
+            .checked_add(referral_fee)
+            .ok_or(ErrorCode::MathOverflow)?;
+
+        Ok(())
+    }
+
+    fn bucket_mut(&mut self, bucket: VaultBucket) -> &mut u64 {
+        match bucket {
+            VaultBucket::Treasury => &mut self.treasury_balance,
+            VaultBucket::ClaimPool => &mut self.claim_pool,
+        }
+    }
+}
+
+// 1 <= threshold <= n <= MAX_ADMIN_SIGNERS, without duplicate or default keys
+pub fn validate_admin_signers(signers: &[Pubkey], threshold: u8) -> Result<()> {
+    require!(
+        !signers.is_empty()
+            && signers.len() <= MAX_ADMIN_SIGNERS
+            && threshold >= 1
+            && threshold as usize <= signers.len(),
+        ErrorCode::InvalidAdminSigners
+    );
+    for (i, signer) in signers.iter().enumerate() {
+        require!(
+            *signer != Pubkey::default() && !signers[..i].contains(signer),
+            ErrorCode::InvalidAdminSigners
+        );
+    }
+    Ok(())
+}
+
+fn fee_of(amount: u64, bps: u16) -> Result<u64> {
+    let fee = (amount as u128)
+        .checked_mul(bps as u128)
+        .ok_or(ErrorCode::MathOverflow)?
+        / BPS_DENOMINATOR as u128;
+
+    u64::try_from(fee).map_err(|_| error!(ErrorCode::MathOverflow))
+}
+
+// buy amounts must sit on min_amount + n * step; every XDEGEN leg is capped at max_amount
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq, Eq, InitSpace)]
+pub struct TradeTiers {
+    pub min_amount: u64,
+    pub max_amount: u64,
+    pub step: u64,
+}
+
+impl TradeTiers {
+    pub fn validate(&self) -> Result<()> {
+        require!(
+            self.step > 0 && self.min_amount > 0 && self.min_amount <= self.max_amount,
+            ErrorCode::InvalidTradeTiers
+        );
+        Ok(())
+    }
+
+    pub fn is_tier(&self, amount: u64) -> bool {
+        amount >= self.min_amount
+            && amount <= self.max_amount
+            && (amount - self.min_amount).checked_rem(self.step) == Some(0)
+    }
+
+    pub fn within_cap(&self, amount: u64) -> bool {
+        amount <= self.max_amount
+    }
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq, Eq, InitSpace)]
+pub struct RoleMember {
+    pub key: Pubkey,
+    pub roles: u8,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq, Eq, InitSpace)]
+pub enum VaultBucket {
+    Treasury,
+    ClaimPool,
+}
+
 #[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug)]
 pub struct TokenParams {
     pub name: String,
     pub symbol: String,
     pub decimals: u8,
     pub uri: String,
-    pub supply: u64
-}
-
-pub const ALLOWED_AMOUNTS: [u64; 10] = [
-    500_000_000, 
-    1_000_000_000, 
-    1_500_000_000, 
-    2_000_000_000, 
-    2_500_000_000, 
-    3_000_000_000, 
-    3_500_000_000, 
-    4_000_000_000, 
-    4_500_000_000, 
-    5_000_000_000
-]; 
\ No newline at end of file
+    pub supply: u64,
+    pub max_supply: u64,
+    pub fixed_supply: bool,
+    pub curve_type: CurveType,
+    pub metadata_standard: MetadataStandard
+}
+
+impl TokenParams {
+    pub fn metadata(&self) -> TokenMetadata {
+        TokenMetadata {
+            name: self.name.clone(),
+            symbol: self.symbol.clone(),
+            uri: self.uri.clone(),
+            decimals: self.decimals,
+            standard: self.metadata_standard,
+            is_mutable: true,
+        }
+    }
+
+    pub fn validate(&self) -> Result<()> {
+        self.metadata().validate()?;
+        require!(self.decimals <= MAX_DECIMALS, ErrorCode::InvalidDecimals);
+
+        // supply and max_supply are raw amounts, so the whole-token bounds scale with decimals;
+        // at high decimals the upper bound is capped by what a u64 can hold
+        let unit = 10u64.pow(self.decimals as u32);
+        let max_supply = MAX_TOKEN_SUPPLY.saturating_mul(unit);
+        require!(
+            (MIN_TOKEN_SUPPLY * unit..=max_supply).contains(&self.supply)
+                && (1..=max_supply).contains(&self.max_supply),
+            ErrorCode::InvalidSupply
+        );
+        Ok(())
+    }
+}
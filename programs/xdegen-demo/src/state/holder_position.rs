@@ -0,0 +1,31 @@
+use anchor_lang::prelude::*;
+
+use crate::error::ErrorCode;
+
+// per (holder, mint) at ["holder_position", holder, mint], opened by anyone holding a created token
+#[account]
+#[derive(InitSpace)]
+pub struct HolderPosition {
+    pub holder: Pubkey,
+    pub mint: Pubkey,
+    pub tokens_sold: u64,
+    pub xdegen_received: u64,
+    pub sell_count: u64,
+    pub opened_at: i64,
+    pub bump: u8
+}
+
+impl HolderPosition {
+    pub fn record_sell(&mut self, tokens_in: u64, xdegen_out: u64) -> Result<()> {
+        self.tokens_sold = self.tokens_sold
+            .checked_add(tokens_in)
+            .ok_or(ErrorCode::MathOverflow)?;
+        self.xdegen_received = self.xdegen_received
+            .checked_add(xdegen_out)
+            .ok_or(ErrorCode::MathOverflow)?;
+        self.sell_count = self.sell_count
+            .checked_add(1)
+            .ok_or(ErrorCode::MathOverflow)?;
+        Ok(())
+    }
+}
\ No newline at end of file
@@ -2,4 +2,25 @@ pub mod config;
 pub use config::*;
 
 pub mod token_record;
-pub use token_record::*;
\ No newline at end of file
+pub use token_record::*;
+
+pub mod bonding_curve;
+pub use bonding_curve::*;
+
+pub mod claim_record;
+pub use claim_record::*;
+
+pub mod referral_account;
+pub use referral_account::*;
+
+pub mod symbol_record;
+pub use symbol_record::*;
+
+pub mod holder_position;
+pub use holder_position::*;
+
+pub mod admin_proposal;
+pub use admin_proposal::*;
+
+pub mod pending_withdrawal;
+pub use pending_withdrawal::*;
\ No newline at end of file
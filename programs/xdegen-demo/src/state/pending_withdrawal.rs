@@ -0,0 +1,25 @@
+use anchor_lang::prelude::*;
+
+use crate::{VaultBucket, error::ErrorCode};
+
+// one per queued vault withdrawal at ["pending_withdrawal", id], ids come from
+// Config.withdrawal_count; closed back to `payer` on execute or cancel
+#[account]
+#[derive(InitSpace)]
+pub struct PendingWithdrawal {
+    pub id: u64,
+    pub payer: Pubkey,
+    pub destination: Pubkey,
+    pub amount: u64,
+    pub bucket: VaultBucket,
+    pub queued_at: i64,
+    pub executable_at: i64,
+    pub bump: u8
+}
+
+impl PendingWithdrawal {
+    pub fn require_unlocked(&self, now: i64) -> Result<()> {
+        require!(now >= self.executable_at, ErrorCode::WithdrawalLocked);
+        Ok(())
+    }
+}
\ No newline at end of file
@@ -0,0 +1,63 @@
+use anchor_lang::prelude::*;
+
+use crate::error::ErrorCode;
+
+#[account]
+#[derive(InitSpace)]
+pub struct ReferralAccount {
+    pub owner: Pubkey,
+    // set on the owner's first referred trade and never changed
+    pub referrer: Option<Pubkey>,
+    pub referral_count: u64,
+    pub rewards: u64,
+    pub total_rewards: u64,
+    pub bump: u8
+}
+
+impl ReferralAccount {
+    /// Binds `self` to `referrer` on first use, afterwards only the same referrer is accepted.
+    /// A wallet that already referred someone can't take a referrer, so the referral graph
+    /// stays a forest and loops are impossible.
+    pub fn link(&mut self, referrer: &mut ReferralAccount) -> Result<()> {
+        require_keys_neq!(self.owner, referrer.owner, ErrorCode::SelfReferral);
+
+        match self.referrer {
+            Some(current) => {
+                require_keys_eq!(current, referrer.owner, ErrorCode::InvalidReferrer);
+            }
+            None => {
+                require!(self.referral_count == 0, ErrorCode::ReferralLoop);
+                self.referrer = Some(referrer.owner);
+                referrer.referral_count = referrer.referral_count
+                    .checked_add(1)
+                    .ok_or(ErrorCode::MathOverflow)?;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// The account behind an unchecked `["referral", owner]` PDA, `None` until the owner created it.
+    pub fn load(info: &AccountInfo) -> Result<Option<ReferralAccount>> {
+        if info.owner != &crate::ID || info.data_is_empty() {
+            return Ok(None);
+        }
+        ReferralAccount::try_deserialize(&mut &info.try_borrow_data()?[..]).map(Some)
+    }
+
+    pub fn store(&self, info: &AccountInfo) -> Result<()> {
+        self.try_serialize(&mut &mut info.try_borrow_mut_data()?[..])
+    }
+
+    pub fn accrue(&mut self, amount: u64) -> Result<()> {
+        self.rewards = self.rewards
+            .checked_add(amount)
+            .ok_or(ErrorCode::MathOverflow)?;
+
+        self.total_rewards = self.total_rewards
+            .checked_add(amount)
+            .ok_or(ErrorCode::MathOverflow)?;
+
+        Ok(())
+    }
+}
\ No newline at end of file
@@ -0,0 +1,31 @@
+use anchor_lang::prelude::*;
+
+use crate::{MAX_SYMBOL_LEN, error::ErrorCode};
+
+// one per symbol at ["symbol", symbol], held by the mint that registered it or reserved by the admin
+#[account]
+#[derive(InitSpace)]
+pub struct SymbolRecord {
+    #[max_len(32)]
+    pub symbol: String,
+    // None while the symbol is reserved by the admin
+    pub mint: Option<Pubkey>,
+    pub bump: u8
+}
+
+impl SymbolRecord {
+    // seeds are capped at 32 bytes, clamp so an oversized symbol still reaches validate_symbol
+    pub fn seed(symbol: &str) -> &[u8] {
+        let bytes = symbol.as_bytes();
+        &bytes[..bytes.len().min(MAX_SYMBOL_LEN)]
+    }
+
+    // records are init_if_needed, a fresh one has no symbol yet while taken and reserved ones do
+    pub fn register(&mut self, symbol: String, mint: Option<Pubkey>, bump: u8) -> Result<()> {
+        require!(self.symbol.is_empty(), ErrorCode::SymbolTaken);
+        self.symbol = symbol;
+        self.mint = mint;
+        self.bump = bump;
+        Ok(())
+    }
+}
\ No newline at end of file
@@ -1,5 +1,14 @@
 use anchor_lang::prelude::*;
 
+use crate::error::ErrorCode;
+
+// kept in step with the #[max_len] attributes on TokenMetadata
+pub const MAX_NAME_LEN: usize = 60;
+pub const MAX_SYMBOL_LEN: usize = 32;
+pub const MAX_URI_LEN: usize = 250;
+// metadata json has to live somewhere a wallet can fetch it without trusting a random host scheme
+pub const URI_SCHEMES: [&str; 3] = ["https://", "ipfs://", "ar://"];
+
 #[account]
 #[derive(InitSpace)]
 pub struct TokenRecord {
@@ -7,10 +16,29 @@ pub struct TokenRecord {
     pub owner: Pubkey,
     pub balance: u64,
     pub metadata: TokenMetadata,
+    // mint supply ceiling; with fixed_supply set mint_token is disabled for good
+    pub max_supply: u64,
+    pub fixed_supply: bool,
     pub created_at: i64,
+    // creator share of trade fees on this mint, waiting in the fee vault
+    pub creator_fees: u64,
     pub bump: u8
 }
 
+impl TokenRecord {
+    /// Reads a record passed unchecked, `None` once the creator closed it.
+    pub fn load(info: &AccountInfo) -> Result<Option<TokenRecord>> {
+        if info.owner != &crate::ID || info.data_is_empty() {
+            return Ok(None);
+        }
+        TokenRecord::try_deserialize(&mut &info.try_borrow_data()?[..]).map(Some)
+    }
+
+    pub fn store(&self, info: &AccountInfo) -> Result<()> {
+        self.try_serialize(&mut &mut info.try_borrow_mut_data()?[..])
+    }
+}
+
 #[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug, InitSpace)]
 pub struct TokenMetadata {
     #[max_len(60)]
@@ -20,4 +48,41 @@ pub struct TokenMetadata {
     #[max_len(250)]
     pub uri: String,
     pub decimals: u8,
+    pub standard: MetadataStandard,
+    pub is_mutable: bool,
+}
+
+impl TokenMetadata {
+    pub fn validate(&self) -> Result<()> {
+        require!(!self.name.is_empty(), ErrorCode::NameLengthZero);
+        require!(self.name.len() <= MAX_NAME_LEN, ErrorCode::NameTooLong);
+        validate_symbol(&self.symbol)?;
+        require!(self.uri.len() <= MAX_URI_LEN, ErrorCode::UriTooLong);
+        require!(
+            URI_SCHEMES.iter().any(|scheme| {
+                self.uri.len() > scheme.len() && self.uri.starts_with(scheme)
+            }),
+            ErrorCode::InvalidUri
+        );
+        Ok(())
+    }
+}
+
+// uppercase alphanumerics only, so a valid symbol is already in the form the symbol registry is keyed by
+pub fn validate_symbol(symbol: &str) -> Result<()> {
+    require!(!symbol.is_empty(), ErrorCode::SymbolLengthZero);
+    require!(symbol.len() <= MAX_SYMBOL_LEN, ErrorCode::SymbolTooLong);
+    require!(
+        symbol.bytes().all(|b| b.is_ascii_uppercase() || b.is_ascii_digit()),
+        ErrorCode::InvalidSymbol
+    );
+    Ok(())
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq, Eq, InitSpace)]
+pub enum MetadataStandard {
+    // SPL Token mint with a Metaplex metadata account
+    Metaplex,
+    // Token-2022 mint holding its own metadata through the MetadataPointer extension
+    Token2022,
 }
\ No newline at end of file
@@ -2,13 +2,15 @@ import * as anchor from "@coral-xyz/anchor";
 import { Program } from "@coral-xyz/anchor";
 import { XdegenDemo } from "../target/types/xdegen_demo";
 import { PublicKey, Keypair, SystemProgram, LAMPORTS_PER_SOL } from "@solana/web3.js";
-import { TOKEN_PROGRAM_ID, createMint, createAssociatedTokenAccount, mintTo, getAssociatedTokenAddress, getAccount, getOrCreateAssociatedTokenAccount, ASSOCIATED_TOKEN_PROGRAM_ID, getMint } from "@solana/spl-token";
+import { TOKEN_PROGRAM_ID, TOKEN_2022_PROGRAM_ID, getTokenMetadata, createMint, createAssociatedTokenAccount, mintTo, getAssociatedTokenAddress, getAccount, getOrCreateAssociatedTokenAccount, ASSOCIATED_TOKEN_PROGRAM_ID, getMint, transfer, getAssociatedTokenAddressSync } from "@solana/spl-token";
 import { expect } from "chai";
 import * as fs from "fs"
 import * as path from "path"
 import { min } from "bn.js";
 
 const METADATA_PROGRAM_ID = new PublicKey("metaqbxxUerdq28cj1RbAWkYQm3ybzjb6a8bt518x1s");
+const MAX_U64 = new anchor.BN("18446744073709551615");
+const BPF_LOADER_UPGRADEABLE_ID = new PublicKey("BPFLoaderUpgradeab1e11111111111111111111111");
 
 describe("xdegen-demo", () => {
   // Configure the client to use the local cluster.
@@ -36,6 +38,7 @@ describe("xdegen-demo", () => {
   const KEYPAIRS_DIR = path.join(__dirname, "../keypairs");
 
   const newMint = anchor.web3.Keypair.generate();
+  const extMint = anchor.web3.Keypair.generate();
 
   async function airdrop(wallet: PublicKey) {
     const balance = await provider.connection.getBalance(wallet);
@@ -103,6 +106,27 @@ describe("xdegen-demo", () => {
     console.log(`✓ Saved xDegen mint address: ${mintAddress.toString()}`);
   }
 
+  // emit_cpi! events live in the inner self-CPI data, after the 8 byte event tag
+  async function getCpiEvents(signature: string) {
+    const latestBlockhash = await provider.connection.getLatestBlockhash();
+    await provider.connection.confirmTransaction({ signature, ...latestBlockhash }, "confirmed");
+    const tx = await provider.connection.getTransaction(signature, {
+      commitment: "confirmed",
+      maxSupportedTransactionVersion: 0,
+    });
+    const events = [];
+    for (const inner of tx?.meta?.innerInstructions ?? []) {
+      for (const ix of inner.instructions) {
+        const data = anchor.utils.bytes.bs58.decode(ix.data);
+        const event = program.coder.events.decode(
+          anchor.utils.bytes.base64.encode(Buffer.from(data.subarray(8)))
+        );
+        if (event) events.push(event);
+      }
+    }
+    return events;
+  }
+
   function getConfigPDA() {
     return anchor.web3.PublicKey.findProgramAddressSync(
       [Buffer.from("config")],
@@ -120,6 +144,87 @@ describe("xdegen-demo", () => {
     )[0]
   }
 
+  function getPendingWithdrawalPDA(id: anchor.BN) {
+    return anchor.web3.PublicKey.findProgramAddressSync(
+      [
+        Buffer.from("pending_withdrawal"),
+        id.toArrayLike(Buffer, "le", 8)
+      ],
+      program.programId
+    )[0]
+  }
+
+  function getFeeVaultPDA() {
+    return PublicKey.findProgramAddressSync(
+      [
+        Buffer.from("fee_vault"),
+        xdegenMint.toBuffer()
+      ],
+      program.programId
+    )[0]
+  }
+
+  function getReferralPDA(owner) {
+    return PublicKey.findProgramAddressSync(
+      [
+        Buffer.from("referral"),
+        owner.toBuffer()
+      ],
+      program.programId
+    )[0]
+  }
+
+  function getReservePDA(mint) {
+    return PublicKey.findProgramAddressSync(
+      [
+        Buffer.from("reserve"),
+        mint.toBuffer()
+      ],
+      program.programId
+    )[0]
+  }
+
+  function getMintAuthorityPDA(mint) {
+    return PublicKey.findProgramAddressSync(
+      [
+        Buffer.from("mint_authority"),
+        mint.toBuffer()
+      ],
+      program.programId
+    )[0]
+  }
+
+  function getBondingCurvePDA(mint) {
+    return PublicKey.findProgramAddressSync(
+      [
+        Buffer.from("bonding_curve"),
+        mint.toBuffer()
+      ],
+      program.programId
+    )[0]
+  }
+
+  function getSymbolRecordPDA(symbol: string) {
+    return PublicKey.findProgramAddressSync(
+      [
+        Buffer.from("symbol"),
+        Buffer.from(symbol).subarray(0, 32)
+      ],
+      program.programId
+    )[0]
+  }
+
+  function getHolderPositionPDA(mint, holder) {
+    return PublicKey.findProgramAddressSync(
+      [
+        Buffer.from("holder_position"),
+        holder.toBuffer(),
+        mint.toBuffer()
+      ],
+      program.programId
+    )[0]
+  }
+
   function getTokenRecordPDA(mint, trader) {
     const [tokenRecordPDA] = PublicKey.findProgramAddressSync(
       [
@@ -132,6 +237,70 @@ describe("xdegen-demo", () => {
     return tokenRecordPDA
   }
 
+  async function buy(
+    tokenParams,
+    trader: Keypair = trader1,
+    opts: { mint?: Keypair, amount?: number, minTokensOut?: anchor.BN, referrer?: PublicKey, mintTokenProgram?: PublicKey } = {},
+  ) {
+    const mint = opts.mint ?? Keypair.generate();
+    const metaplex = "metaplex" in tokenParams.metadataStandard;
+    const mintTokenProgram = opts.mintTokenProgram ?? (metaplex ? TOKEN_PROGRAM_ID : TOKEN_2022_PROGRAM_ID);
+    const metadata = metaplex
+      ? PublicKey.findProgramAddressSync(
+        [Buffer.from("metadata"), METADATA_PROGRAM_ID.toBuffer(), mint.publicKey.toBuffer()],
+        METADATA_PROGRAM_ID
+      )[0]
+      : null;
+
+    return program.methods.buy(tokenParams, new anchor.BN(opts.amount ?? 500_000_000), opts.minTokensOut ?? new anchor.BN(0)).accountsPartial({
+      sessionToken: null,
+      trader: trader.publicKey,
+      config: getConfigPDA(),
+      mint: mint.publicKey,
+      mintAuthority: getMintAuthorityPDA(mint.publicKey),
+      traderMintAta: getAssociatedTokenAddressSync(mint.publicKey, trader.publicKey, false, mintTokenProgram),
+      metadata,
+      xdegenMint,
+      reserve: getReservePDA(mint.publicKey),
+      feeVault: getFeeVaultPDA(),
+      traderReferral: getReferralPDA(trader.publicKey),
+      referrerReferral: opts.referrer ? getReferralPDA(opts.referrer) : null,
+      traderXdegenAta: getAssociatedTokenAddressSync(xdegenMint, trader.publicKey),
+      tokenRecord: getTokenRecordPDA(mint.publicKey, trader.publicKey),
+      bondingCurve: getBondingCurvePDA(mint.publicKey),
+      symbolRecord: getSymbolRecordPDA(tokenParams.symbol),
+      tokenProgram: TOKEN_PROGRAM_ID,
+      mintTokenProgram,
+      tokenMetadataProgram: metaplex ? METADATA_PROGRAM_ID : null,
+      systemProgram: SystemProgram.programId,
+    }).signers([trader, mint]).rpc();
+  }
+
+  async function mintMore(
+    mint: PublicKey,
+    mintAmount: number,
+    opts: { referrer?: PublicKey, maxXdegenIn?: anchor.BN } = {},
+  ) {
+    return program.methods.mintToken(new anchor.BN(mintAmount), opts.maxXdegenIn ?? MAX_U64).accountsPartial({
+      sessionToken: null,
+      trader: trader1.publicKey,
+      config: getConfigPDA(),
+      mint,
+      xdegenMint,
+      reserve: getReservePDA(mint),
+      feeVault: getFeeVaultPDA(),
+      traderReferral: getReferralPDA(trader1.publicKey),
+      referrerReferral: opts.referrer ? getReferralPDA(opts.referrer) : null,
+      traderXdegenAta: getAssociatedTokenAddressSync(xdegenMint, trader1.publicKey),
+      traderMintAta: getAssociatedTokenAddressSync(mint, trader1.publicKey),
+      mintAuthority: getMintAuthorityPDA(mint),
+      bondingCurve: getBondingCurvePDA(mint),
+      tokenProgram: TOKEN_PROGRAM_ID,
+      mintTokenProgram: TOKEN_PROGRAM_ID,
+      systemProgram: SystemProgram.programId,
+    }).signers([trader1]).rpc();
+  }
+
   before(async () => {
     console.log("\n========================================");
     console.log("         SETUP & INITIALIZATION         ");
@@ -358,12 +527,56 @@ describe("xdegen-demo", () => {
   })
 
   describe("Initialize", () => {
+    const programData = PublicKey.findProgramAddressSync(
+      [program.programId.toBuffer()],
+      BPF_LOADER_UPGRADEABLE_ID
+    )[0];
+
+    it("should fail for a signer other than the upgrade authority", async () => {
+      try {
+        await program.methods.initialize().accountsPartial({
+          admin: trader1.publicKey,
+          xdegenDemoProgram: program.programId,
+          programData,
+          config: getConfigPDA(),
+          xdegenMint,
+          vault: getVaultPDA(),
+          feeVault: getFeeVaultPDA(),
+          tokenProgram: TOKEN_PROGRAM_ID,
+          systemProgram: SystemProgram.programId,
+        }).signers([trader1]).rpc();
+        expect.fail("Should have failed");
+      } catch (error) {
+        expect(error.message).to.include("Not upgrade authority");
+      }
+    });
+
     it("should initialize successfully", async () => {
+      // the deploy wallet holds the upgrade authority, hand it to the admin keypair first;
+      // ProgramData is a u32 tag, u64 slot and Option<Pubkey> authority
+      const { data } = await connection.getAccountInfo(programData);
+      const authority = data[12] === 1 ? new PublicKey(data.subarray(13, 45)) : null;
+      if (authority && authority.equals(wallet.publicKey) && !authority.equals(admin.publicKey)) {
+        const setAuthority = new anchor.web3.TransactionInstruction({
+          programId: BPF_LOADER_UPGRADEABLE_ID,
+          keys: [
+            { pubkey: programData, isSigner: false, isWritable: true },
+            { pubkey: wallet.publicKey, isSigner: true, isWritable: false },
+            { pubkey: admin.publicKey, isSigner: false, isWritable: false },
+          ],
+          data: Buffer.from([4, 0, 0, 0]),
+        });
+        await provider.sendAndConfirm(new anchor.web3.Transaction().add(setAuthority));
+      }
+
       await program.methods.initialize().accountsPartial({
         admin: admin.publicKey,
+        xdegenDemoProgram: program.programId,
+        programData,
         config: getConfigPDA(),
         xdegenMint,
         vault: getVaultPDA(),
+        feeVault: getFeeVaultPDA(),
         tokenProgram: TOKEN_PROGRAM_ID,
         systemProgram: SystemProgram.programId,
       }).signers([admin]).rpc();
@@ -375,6 +588,213 @@ describe("xdegen-demo", () => {
       expect(configAccount.totalBuys.toNumber()).to.equal(0);
       expect(configAccount.totalSells.toNumber()).to.equal(0);
       expect(configAccount.totalClaimed.toNumber()).to.equal(0);
+      expect(configAccount.tradeTiers.minAmount.toNumber()).to.equal(500_000_000);
+      expect(configAccount.tradeTiers.maxAmount.toNumber()).to.equal(5_000_000_000);
+      expect(configAccount.tradeTiers.step.toNumber()).to.equal(500_000_000);
+      expect(configAccount.feeVault.toBase58()).to.equal(getFeeVaultPDA().toBase58());
+      expect(configAccount.protocolFeeBps).to.equal(100);
+      expect(configAccount.creatorFeeBps).to.equal(100);
+    });
+  });
+
+  describe("UpdateConfig", () => {
+    it("should update claim amount and emit old and new values", async () => {
+      const configBefore = await program.account.config.fetch(getConfigPDA());
+      const newClaimAmount = new anchor.BN(25_000_000_000);
+
+      const signature = await program.methods.updateConfig({ claimAmount: newClaimAmount, claimCooldown: null, maxClaimPerWallet: null, protocolFeeBps: null, creatorFeeBps: null, referralFeeBps: null, withdrawDelay: null, withdrawCap: null }).accountsPartial({
+        authority: admin.publicKey,
+        config: getConfigPDA(),
+      }).signers([admin]).rpc();
+      const event = (await getCpiEvents(signature)).find((e) => e.name === "configUpdated");
+
+      const configAfter = await program.account.config.fetch(getConfigPDA());
+      expect(configAfter.claimAmount.toString()).to.equal(newClaimAmount.toString());
+      expect(event).to.not.be.undefined;
+      expect(event.data.oldClaimAmount.toString()).to.equal(configBefore.claimAmount.toString());
+      expect(event.data.newClaimAmount.toString()).to.equal(newClaimAmount.toString());
+
+      await program.methods.updateConfig({ claimAmount: configBefore.claimAmount, claimCooldown: null, maxClaimPerWallet: null, protocolFeeBps: null, creatorFeeBps: null, referralFeeBps: null, withdrawDelay: null, withdrawCap: null }).accountsPartial({
+        authority: admin.publicKey,
+        config: getConfigPDA(),
+      }).signers([admin]).rpc();
+    });
+
+    it("should fail with zero claim amount", async () => {
+      try {
+        await program.methods.updateConfig({ claimAmount: new anchor.BN(0), claimCooldown: null, maxClaimPerWallet: null, protocolFeeBps: null, creatorFeeBps: null, referralFeeBps: null, withdrawDelay: null, withdrawCap: null }).accountsPartial({
+          authority: admin.publicKey,
+          config: getConfigPDA(),
+        }).signers([admin]).rpc();
+        expect.fail("Should have failed");
+      } catch (error) {
+        expect(error.message).to.include("Invalid amount");
+      }
+    });
+
+    it("should fail with a claim amount above the per-wallet cap", async () => {
+      const configBefore = await program.account.config.fetch(getConfigPDA());
+      try {
+        await program.methods.updateConfig({ claimAmount: configBefore.maxClaimPerWallet.addn(1), claimCooldown: null, maxClaimPerWallet: null, protocolFeeBps: null, creatorFeeBps: null, referralFeeBps: null, withdrawDelay: null, withdrawCap: null }).accountsPartial({
+          authority: admin.publicKey,
+          config: getConfigPDA(),
+        }).signers([admin]).rpc();
+        expect.fail("Should have failed");
+      } catch (error) {
+        expect(error.message).to.include("Invalid amount");
+      }
+    });
+
+    it("should fail with fees above the cap", async () => {
+      try {
+        await program.methods.updateConfig({ claimAmount: null, claimCooldown: null, maxClaimPerWallet: null, protocolFeeBps: 900, creatorFeeBps: 101, referralFeeBps: null, withdrawDelay: null, withdrawCap: null }).accountsPartial({
+          authority: admin.publicKey,
+          config: getConfigPDA(),
+        }).signers([admin]).rpc();
+        expect.fail("Should have failed");
+      } catch (error) {
+        expect(error.message).to.include("Invalid fee");
+      }
+    });
+
+    it("should fail with unauthorized", async () => {
+      try {
+        await program.methods.updateConfig({ claimAmount: new anchor.BN(1), claimCooldown: null, maxClaimPerWallet: null, protocolFeeBps: null, creatorFeeBps: null, referralFeeBps: null, withdrawDelay: null, withdrawCap: null }).accountsPartial({
+          authority: trader1.publicKey,
+          config: getConfigPDA(),
+        }).signers([trader1]).rpc();
+        expect.fail("Should have failed");
+      } catch (error) {
+        expect(error.message).to.include("Missing role");
+      }
+    });
+  });
+
+  describe("UpdateTradeTiers", () => {
+    const defaultTiers = {
+      minAmount: new anchor.BN(500_000_000),
+      maxAmount: new anchor.BN(5_000_000_000),
+      step: new anchor.BN(500_000_000),
+    };
+
+    it("should update trade tiers successfully", async () => {
+      const tiers = {
+        minAmount: new anchor.BN(250_000_000),
+        maxAmount: new anchor.BN(10_000_000_000),
+        step: new anchor.BN(250_000_000),
+      };
+      await program.methods.updateTradeTiers(tiers).accountsPartial({
+        authority: admin.publicKey,
+        config: getConfigPDA(),
+      }).signers([admin]).rpc();
+
+      const configAccount = await program.account.config.fetch(getConfigPDA());
+      expect(configAccount.tradeTiers.minAmount.toNumber()).to.equal(250_000_000);
+      expect(configAccount.tradeTiers.maxAmount.toNumber()).to.equal(10_000_000_000);
+
+      await program.methods.updateTradeTiers(defaultTiers).accountsPartial({
+        authority: admin.publicKey,
+        config: getConfigPDA(),
+      }).signers([admin]).rpc();
+    });
+
+    it("should fail with a zero step", async () => {
+      try {
+        await program.methods.updateTradeTiers({ ...defaultTiers, step: new anchor.BN(0) }).accountsPartial({
+          authority: admin.publicKey,
+          config: getConfigPDA(),
+        }).signers([admin]).rpc();
+        expect.fail("Should have failed");
+      } catch (error) {
+        expect(error.message).to.include("Invalid trade tiers");
+      }
+    });
+
+    it("should fail with unauthorized", async () => {
+      try {
+        await program.methods.updateTradeTiers(defaultTiers).accountsPartial({
+          authority: trader1.publicKey,
+          config: getConfigPDA(),
+        }).signers([trader1]).rpc();
+        expect.fail("Should have failed");
+      } catch (error) {
+        expect(error.message).to.include("Missing role");
+      }
+    });
+  });
+
+  describe("AdminTransfer", () => {
+    it("should not let anyone but the pending admin accept", async () => {
+      await program.methods.proposeAdmin(trader2.publicKey).accountsPartial({
+        admin: admin.publicKey,
+        config: getConfigPDA(),
+      }).signers([admin]).rpc();
+
+      try {
+        await program.methods.acceptAdmin().accountsPartial({
+          newAdmin: trader1.publicKey,
+          config: getConfigPDA(),
+        }).signers([trader1]).rpc();
+        expect.fail("Should have failed");
+      } catch (error) {
+        expect(error.message).to.include("Unauthorized");
+      }
+    });
+
+    it("should cancel a pending transfer", async () => {
+      await program.methods.cancelAdminTransfer().accountsPartial({
+        admin: admin.publicKey,
+        config: getConfigPDA(),
+      }).signers([admin]).rpc();
+
+      const configAccount = await program.account.config.fetch(getConfigPDA());
+      expect(configAccount.pendingAdmin).to.be.null;
+      expect(configAccount.admin.toBase58()).to.equal(admin.publicKey.toBase58());
+    });
+
+    it("should transfer admin once accepted and hand it back", async () => {
+      await program.methods.proposeAdmin(trader2.publicKey).accountsPartial({
+        admin: admin.publicKey,
+        config: getConfigPDA(),
+      }).signers([admin]).rpc();
+      await program.methods.acceptAdmin().accountsPartial({
+        newAdmin: trader2.publicKey,
+        config: getConfigPDA(),
+      }).signers([trader2]).rpc();
+
+      let configAccount = await program.account.config.fetch(getConfigPDA());
+      expect(configAccount.admin.toBase58()).to.equal(trader2.publicKey.toBase58());
+      expect(configAccount.pendingAdmin).to.be.null;
+      // the signer seat and roles move with the admin key
+      expect(configAccount.adminSigners.map((k) => k.toBase58())).to.deep.equal([trader2.publicKey.toBase58()]);
+      expect(configAccount.roleMembers.some((m) => m.key.equals(admin.publicKey))).to.be.false;
+      expect(configAccount.roleMembers.find((m) => m.key.equals(trader2.publicKey)).roles).to.equal(15);
+
+      await program.methods.proposeAdmin(admin.publicKey).accountsPartial({
+        admin: trader2.publicKey,
+        config: getConfigPDA(),
+      }).signers([trader2]).rpc();
+      await program.methods.acceptAdmin().accountsPartial({
+        newAdmin: admin.publicKey,
+        config: getConfigPDA(),
+      }).signers([admin]).rpc();
+
+      configAccount = await program.account.config.fetch(getConfigPDA());
+      expect(configAccount.admin.toBase58()).to.equal(admin.publicKey.toBase58());
+      expect(configAccount.adminSigners.map((k) => k.toBase58())).to.deep.equal([admin.publicKey.toBase58()]);
+      expect(configAccount.roleMembers.find((m) => m.key.equals(admin.publicKey)).roles).to.equal(15);
+    });
+
+    it("should fail to propose from a non-admin", async () => {
+      try {
+        await program.methods.proposeAdmin(trader1.publicKey).accountsPartial({
+          admin: trader1.publicKey,
+          config: getConfigPDA(),
+        }).signers([trader1]).rpc();
+        expect.fail("Should have failed");
+      } catch (error) {
+        expect(error.message).to.include("Unauthorized");
+      }
     });
   });
 
@@ -384,10 +804,10 @@ describe("xdegen-demo", () => {
       const vaultBefore = await getAccount(connection, getVaultPDA());
       const adminBefore = await getAccount(connection, adminXdegenATA);
 
-      await program.methods.deposit(new anchor.BN(amount)).accountsPartial({
-        admin: admin.publicKey,
+      await program.methods.deposit(new anchor.BN(amount), { claimPool: {} }).accountsPartial({
+        authority: admin.publicKey,
         config: getConfigPDA(),
-        adminTokenAccount: adminXdegenATA,
+        authorityTokenAccount: adminXdegenATA,
         mint: xdegenMint,
         vault: getVaultPDA(),
         tokenProgram: TOKEN_PROGRAM_ID,
@@ -398,14 +818,18 @@ describe("xdegen-demo", () => {
 
       expect(vaultAfter.amount - vaultBefore.amount).to.equal(BigInt(amount));
       expect(adminBefore.amount - adminAfter.amount).to.equal(BigInt(amount));
+
+      const configAccount = await program.account.config.fetch(getConfigPDA());
+      expect(configAccount.claimPool.toNumber()).to.equal(amount);
+      expect(configAccount.treasuryBalance.toNumber()).to.equal(0);
     });
 
     it("should fail with invalid amount", async () => {
       try {
-        await program.methods.deposit(new anchor.BN(0)).accountsPartial({
-          admin: admin.publicKey,
+        await program.methods.deposit(new anchor.BN(0), { claimPool: {} }).accountsPartial({
+          authority: admin.publicKey,
           config: getConfigPDA(),
-          adminTokenAccount: walletXdegenATA,
+          authorityTokenAccount: walletXdegenATA,
           mint: xdegenMint,
           vault: getVaultPDA(),
           tokenProgram: TOKEN_PROGRAM_ID,
@@ -419,10 +843,10 @@ describe("xdegen-demo", () => {
     it("should fail with insufficient funds", async () => {
       const amount = 700_000_000_000; // more than available
       try {
-        await program.methods.deposit(new anchor.BN(amount)).accountsPartial({
-          admin: admin.publicKey,
+        await program.methods.deposit(new anchor.BN(amount), { claimPool: {} }).accountsPartial({
+          authority: admin.publicKey,
           config: getConfigPDA(),
-          adminTokenAccount: adminXdegenATA,
+          authorityTokenAccount: adminXdegenATA,
           mint: xdegenMint,
           vault: getVaultPDA(),
           tokenProgram: TOKEN_PROGRAM_ID,
@@ -442,10 +866,10 @@ describe("xdegen-demo", () => {
         9
       );
       try {
-        await program.methods.deposit(new anchor.BN(1_000_000_000)).accountsPartial({
-          admin: admin.publicKey,
+        await program.methods.deposit(new anchor.BN(1_000_000_000), { claimPool: {} }).accountsPartial({
+          authority: admin.publicKey,
           config: getConfigPDA(),
-          adminTokenAccount: adminXdegenATA,
+          authorityTokenAccount: adminXdegenATA,
           mint: wrongMint,
           vault: getVaultPDA(),
           tokenProgram: TOKEN_PROGRAM_ID,
@@ -459,98 +883,200 @@ describe("xdegen-demo", () => {
     it("should fail with unauthorized", async () => {
       const wrongAdmin = Keypair.generate();
       try {
-        await program.methods.deposit(new anchor.BN(1_000_000_000)).accountsPartial({
-          admin: wrongAdmin.publicKey,
+        await program.methods.deposit(new anchor.BN(1_000_000_000), { claimPool: {} }).accountsPartial({
+          authority: wrongAdmin.publicKey,
           config: getConfigPDA(),
-          adminTokenAccount: adminXdegenATA,
+          authorityTokenAccount: adminXdegenATA,
           mint: xdegenMint,
           vault: getVaultPDA(),
           tokenProgram: TOKEN_PROGRAM_ID,
         }).signers([wrongAdmin]).rpc();
         expect.fail("Should have failed");
       } catch (error) {
-        expect(error.message).to.include("Unauthorized");
+        expect(error.message).to.include("Missing role");
       }
     });
   });
 
   describe("Withdraw", () => {
-    it("should withdraw successfully", async () => {
-      const amount = 1_000_000_000;
-      const vaultBefore = await getAccount(anchor.getProvider().connection, getVaultPDA());
-      const adminBefore = await getAccount(anchor.getProvider().connection, adminXdegenATA);
+    const noConfigChange = { claimAmount: null, claimCooldown: null, maxClaimPerWallet: null, protocolFeeBps: null, creatorFeeBps: null, referralFeeBps: null, withdrawDelay: null, withdrawCap: null };
 
-      await program.methods.withdraw(new anchor.BN(amount)).accountsPartial({
-        admin: admin.publicKey,
+    async function queue(amount: number, bucket, destination: PublicKey = adminXdegenATA, signer: Keypair = admin) {
+      const { withdrawalCount } = await program.account.config.fetch(getConfigPDA());
+      const signature = await program.methods.queueWithdrawal(new anchor.BN(amount), bucket).accountsPartial({
+        authority: signer.publicKey,
+        config: getConfigPDA(),
+        xdegenMint,
+        destination,
+        pendingWithdrawal: getPendingWithdrawalPDA(withdrawalCount),
+        systemProgram: SystemProgram.programId,
+      }).signers([signer]).rpc();
+      return { pendingWithdrawal: getPendingWithdrawalPDA(withdrawalCount), signature };
+    }
+
+    async function executeWithdrawal(pendingWithdrawal: PublicKey, destination: PublicKey = adminXdegenATA) {
+      return program.methods.executeWithdrawal().accountsPartial({
+        authority: admin.publicKey,
         config: getConfigPDA(),
+        pendingWithdrawal,
+        payer: admin.publicKey,
         xdegenMint,
         vault: getVaultPDA(),
-        adminXdegenAta: adminXdegenATA,
-        associatedTokenProgram: anchor.utils.token.ASSOCIATED_PROGRAM_ID,
+        destination,
         tokenProgram: TOKEN_PROGRAM_ID,
-        systemProgram: SystemProgram.programId,
       }).signers([admin]).rpc();
+    }
 
-      const vaultAfter = await getAccount(anchor.getProvider().connection, getVaultPDA());
-      const adminAfter = await getAccount(anchor.getProvider().connection, adminXdegenATA);
+    function cancel(pendingWithdrawal: PublicKey) {
+      return program.methods.cancelWithdrawal().accountsPartial({
+        authority: admin.publicKey,
+        config: getConfigPDA(),
+        pendingWithdrawal,
+        payer: admin.publicKey,
+      }).signers([admin]).rpc();
+    }
 
-      expect(vaultBefore.amount - vaultAfter.amount).to.equal(BigInt(amount));
-      expect(adminAfter.amount - adminBefore.amount).to.equal(BigInt(amount));
+    it("should start with the default delay and cap", async () => {
+      const configAccount = await program.account.config.fetch(getConfigPDA());
+      expect(configAccount.withdrawDelay.toNumber()).to.equal(172_800);
+      expect(configAccount.withdrawCap.toString()).to.equal((1_000n * 1_000_000_000n).toString());
     });
 
-    it("should fail with invalid amount", async () => {
+    it("should not execute a withdrawal before its delay", async () => {
+      const { pendingWithdrawal } = await queue(1_000_000_000, { claimPool: {} });
       try {
-        await program.methods.withdraw(new anchor.BN(0)).accountsPartial({
-          admin: admin.publicKey,
-          config: getConfigPDA(),
-          xdegenMint,
-          vault: getVaultPDA(),
-          adminXdegenAta: adminXdegenATA,
-          associatedTokenProgram: anchor.utils.token.ASSOCIATED_PROGRAM_ID,
-          tokenProgram: TOKEN_PROGRAM_ID,
-          systemProgram: SystemProgram.programId,
-        }).signers([admin]).rpc();
+        await executeWithdrawal(pendingWithdrawal);
         expect.fail("Should have failed");
       } catch (error) {
-        expect(error.message).to.include("Invalid amount");
+        expect(error.message).to.include("Withdrawal locked");
       }
+
+      await cancel(pendingWithdrawal);
+      expect(await connection.getAccountInfo(pendingWithdrawal)).to.be.null;
     });
 
-    it("should fail with insufficient funds in vault", async () => {
-      const amount = 100_000_000_000;
+    it("should queue a withdrawal to any token account", async () => {
+      const amount = 1_000_000_000;
+      const { pendingWithdrawal, signature } = await queue(amount, { claimPool: {} }, trader1XdegenAta);
+      const queued = (await getCpiEvents(signature)).find((e) => e.name === "withdrawalQueued");
+      expect(queued.data.destination.toBase58()).to.equal(trader1XdegenAta.toBase58());
+
+      const pending = await program.account.pendingWithdrawal.fetch(pendingWithdrawal);
+      expect(pending.amount.toString()).to.equal(amount.toString());
+      expect(pending.executableAt.sub(pending.queuedAt).toNumber()).to.equal(172_800);
+
       try {
-        await program.methods.withdraw(new anchor.BN(amount)).accountsPartial({
-          admin: admin.publicKey,
-          config: getConfigPDA(),
-          xdegenMint,
-          vault: getVaultPDA(),
-          adminXdegenAta: adminXdegenATA,
-          associatedTokenProgram: anchor.utils.token.ASSOCIATED_PROGRAM_ID,
-          tokenProgram: TOKEN_PROGRAM_ID,
-          systemProgram: SystemProgram.programId,
-        }).signers([admin]).rpc();
+        await executeWithdrawal(pendingWithdrawal);
         expect.fail("Should have failed");
       } catch (error) {
-        expect(error.message).to.include("Insufficient funds");
+        expect(error.message).to.include("Invalid destination");
       }
+      try {
+        await executeWithdrawal(pendingWithdrawal, trader1XdegenAta);
+        expect.fail("Should have failed");
+      } catch (error) {
+        expect(error.message).to.include("Withdrawal locked");
+      }
+      await cancel(pendingWithdrawal);
     });
 
-    it("should fail with unauthorized", async () => {
-      const wrongAdmin = Keypair.generate();
+    it("should reject a delay below the minimum", async () => {
       try {
-        await program.methods.withdraw(new anchor.BN(1_000_000_000)).accountsPartial({
-          admin: wrongAdmin.publicKey,
+        await program.methods.updateConfig({ ...noConfigChange, withdrawDelay: new anchor.BN(0) }).accountsPartial({
+          authority: admin.publicKey,
           config: getConfigPDA(),
-          xdegenMint,
-          vault: getVaultPDA(),
-          adminXdegenAta: adminXdegenATA,
-          associatedTokenProgram: anchor.utils.token.ASSOCIATED_PROGRAM_ID,
-          tokenProgram: TOKEN_PROGRAM_ID,
-          systemProgram: SystemProgram.programId,
-        }).signers([wrongAdmin]).rpc();
+        }).signers([admin]).rpc();
         expect.fail("Should have failed");
       } catch (error) {
-        expect(error.message).to.include("Unauthorized");
+        expect(error.message).to.include("Invalid amount");
+      }
+    });
+
+    it("should hold back a shorter delay and a higher cap for a full delay", async () => {
+      const configBefore = await program.account.config.fetch(getConfigPDA());
+      const signature = await program.methods.updateConfig({
+        ...noConfigChange,
+        withdrawDelay: new anchor.BN(3_600),
+        withdrawCap: configBefore.withdrawCap.muln(2),
+      }).accountsPartial({
+        authority: admin.publicKey,
+        config: getConfigPDA(),
+      }).signers([admin]).rpc();
+
+      const configAfter = await program.account.config.fetch(getConfigPDA());
+      expect(configAfter.withdrawDelay.toNumber()).to.equal(172_800);
+      expect(configAfter.withdrawCap.toString()).to.equal(configBefore.withdrawCap.toString());
+      expect(configAfter.pendingWithdrawDelay.toNumber()).to.equal(3_600);
+      expect(configAfter.pendingWithdrawCap.toString()).to.equal(configBefore.withdrawCap.muln(2).toString());
+
+      const event = (await getCpiEvents(signature)).find((e) => e.name === "configUpdated");
+      const { blockTime } = await connection.getTransaction(signature, { commitment: "confirmed", maxSupportedTransactionVersion: 0 });
+      expect(event.data.pendingWithdrawLimitsAt.toNumber()).to.equal(blockTime + 172_800);
+
+      // the cap in force still applies to new withdrawals
+      try {
+        await queue(configBefore.withdrawCap.addn(1).toNumber(), { claimPool: {} });
+        expect.fail("Should have failed");
+      } catch (error) {
+        expect(error.message).to.include("Withdraw cap exceeded");
+      }
+    });
+
+    it("should apply a longer delay and a lower cap at once", async () => {
+      const withdrawCap = new anchor.BN(500_000_000_000);
+      await program.methods.updateConfig({ ...noConfigChange, withdrawDelay: new anchor.BN(172_800), withdrawCap }).accountsPartial({
+        authority: admin.publicKey,
+        config: getConfigPDA(),
+      }).signers([admin]).rpc();
+
+      const configAccount = await program.account.config.fetch(getConfigPDA());
+      expect(configAccount.withdrawCap.toString()).to.equal(withdrawCap.toString());
+      expect(configAccount.pendingWithdrawDelay).to.be.null;
+      expect(configAccount.pendingWithdrawCap).to.be.null;
+
+      try {
+        await queue(withdrawCap.addn(1).toNumber(), { claimPool: {} });
+        expect.fail("Should have failed");
+      } catch (error) {
+        expect(error.message).to.include("Withdraw cap exceeded");
+      }
+    });
+
+    it("should fail with invalid amount", async () => {
+      try {
+        await queue(0, { claimPool: {} });
+        expect.fail("Should have failed");
+      } catch (error) {
+        expect(error.message).to.include("Invalid amount");
+      }
+    });
+
+    it("should fail to queue more than the vault holds", async () => {
+      try {
+        await queue(100_000_000_000, { claimPool: {} });
+        expect.fail("Should have failed");
+      } catch (error) {
+        expect(error.message).to.include("Insufficient funds");
+      }
+    });
+
+    it("should not withdraw claim pool funds from the treasury", async () => {
+      try {
+        await queue(1_000_000_000, { treasury: {} });
+        expect.fail("Should have failed");
+      } catch (error) {
+        expect(error.message).to.include("Insufficient funds");
+      }
+    });
+
+    it("should fail with unauthorized", async () => {
+      const wrongAdmin = Keypair.generate();
+      await airdrop(wrongAdmin.publicKey);
+      try {
+        await queue(1_000_000_000, { claimPool: {} }, adminXdegenATA, wrongAdmin);
+        expect.fail("Should have failed");
+      } catch (error) {
+        expect(error.message).to.include("Missing role");
       }
     });
   });
@@ -563,6 +1089,10 @@ describe("xdegen-demo", () => {
         decimals: 9,
         uri: "https://example.com",
         supply: new anchor.BN(1_000_000_000),
+        maxSupply: new anchor.BN(5_000_000_000),
+        fixedSupply: false,
+        curveType: { constantProduct: {} },
+        metadataStandard: { metaplex: {} },
       };
       const amount = 500_000_000;
       const traderMintAta = await getAssociatedTokenAddress(
@@ -570,29 +1100,7 @@ describe("xdegen-demo", () => {
         trader1.publicKey
       );
 
-      const metadata = PublicKey.findProgramAddressSync(
-        [Buffer.from("metadata"), METADATA_PROGRAM_ID.toBuffer(), newMint.publicKey.toBuffer()],
-        METADATA_PROGRAM_ID
-      )[0];
-
-      const configPDA = getConfigPDA();
-      let tx = await program.methods.buy(tokenParams, new anchor.BN(amount)).accountsPartial({
-        sessionToken: null,
-        trader: trader1.publicKey,
-        config: configPDA,
-        mint: newMint.publicKey,
-        traderMintAta: traderMintAta,
-        metadata: metadata,
-        xdegenMint: xdegenMint,
-        vault: getVaultPDA(),
-        traderXdegenAta: trader1XdegenAta,
-        tokenRecord: getTokenRecordPDA(newMint.publicKey, trader1.publicKey),
-        tokenProgram: TOKEN_PROGRAM_ID,
-        systemProgram: SystemProgram.programId,
-        associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
-        rent: anchor.web3.SYSVAR_RENT_PUBKEY,
-        tokenMetadataProgram: METADATA_PROGRAM_ID,
-      }).signers([trader1, newMint]).rpc();
+      let tx = await buy(tokenParams, trader1, { mint: newMint, amount });
 
       console.log('signature', tx);
 
@@ -600,8 +1108,52 @@ describe("xdegen-demo", () => {
       expect(config.totalTrades.toNumber()).to.equal(1);
       expect(config.totalBuys.toNumber()).to.equal(1);
 
+      const protocolFee = Math.floor(amount * config.protocolFeeBps / 10_000);
+      const creatorFee = Math.floor(amount * config.creatorFeeBps / 10_000);
+      const netAmount = amount - protocolFee - creatorFee;
+
+      const bondingCurve = await program.account.bondingCurve.fetch(getBondingCurvePDA(newMint.publicKey));
+      expect(bondingCurve.realXdegenReserves.toNumber()).to.equal(netAmount);
+
+      const reserve = await getAccount(anchor.getProvider().connection, getReservePDA(newMint.publicKey));
+      expect(reserve.amount).to.equal(BigInt(netAmount));
+      expect(config.totalReserves.toNumber()).to.equal(netAmount);
+      expect(config.protocolFees.toNumber()).to.equal(protocolFee);
+      expect(config.creatorFees.toNumber()).to.equal(creatorFee);
+
+      const tokenRecord = await program.account.tokenRecord.fetch(getTokenRecordPDA(newMint.publicKey, trader1.publicKey));
+      expect(tokenRecord.creatorFees.toNumber()).to.equal(creatorFee);
+
+      const feeVault = await getAccount(anchor.getProvider().connection, getFeeVaultPDA());
+      expect(feeVault.amount).to.equal(BigInt(protocolFee + creatorFee));
+      expect(bondingCurve.virtualTokenReserves.toString()).to.equal(tokenParams.supply.toString());
+
       const traderMintAccount = await getAccount(anchor.getProvider().connection, traderMintAta);
-      expect(traderMintAccount.amount).to.equal(BigInt(tokenParams.supply.toString()));
+      expect(traderMintAccount.amount).to.equal(BigInt(bondingCurve.tokensSold.toString()));
+
+      const mintInfo = await getMint(connection, newMint.publicKey);
+      expect(mintInfo.mintAuthority.toBase58()).to.equal(getMintAuthorityPDA(newMint.publicKey).toBase58());
+      expect(mintInfo.freezeAuthority.toBase58()).to.equal(getMintAuthorityPDA(newMint.publicKey).toBase58());
+    });
+
+    it("should not let the creator mint outside the program", async () => {
+      const traderMintAta = await getAssociatedTokenAddress(
+        newMint.publicKey,
+        trader1.publicKey
+      );
+      try {
+        await mintTo(
+          connection,
+          trader1,
+          newMint.publicKey,
+          traderMintAta,
+          trader1,
+          1_000_000_000
+        );
+        expect.fail("Should have failed");
+      } catch (error) {
+        expect(error.message).to.not.include("Should have failed");
+      }
     });
 
     it("should fail with empty name", async () => {
@@ -611,35 +1163,39 @@ describe("xdegen-demo", () => {
         decimals: 9,
         uri: "https://example.com",
         supply: new anchor.BN(1_000_000_000),
+        maxSupply: new anchor.BN(5_000_000_000),
+        fixedSupply: false,
+        curveType: { constantProduct: {} },
+        metadataStandard: { metaplex: {} },
       };
-      const amount = 500_000_000;
-      const newMint = Keypair.generate();
       try {
-        const metadata = PublicKey.findProgramAddressSync(
-            [Buffer.from("metadata"), METADATA_PROGRAM_ID.toBuffer(), newMint.publicKey.toBuffer()],
-            METADATA_PROGRAM_ID
-          )[0]
-        await program.methods.buy(tokenParams, new anchor.BN(amount)).accountsPartial({
-          trader: trader1.publicKey,
-          config: getConfigPDA(),
-          mint: newMint.publicKey,
-          traderMintAta: await getAssociatedTokenAddress(newMint.publicKey, trader1.publicKey),
-          metadata: metadata,
-          xdegenMint,
-          vault: getVaultPDA(),
-          traderXdegenAta: trader1XdegenAta,
-          rent: anchor.web3.SYSVAR_RENT_PUBKEY,
-          associatedTokenProgram: anchor.utils.token.ASSOCIATED_PROGRAM_ID,
-          tokenMetadataProgram: METADATA_PROGRAM_ID,
-          tokenProgram: TOKEN_PROGRAM_ID,
-          systemProgram: SystemProgram.programId,
-        }).signers([trader1, newMint]).rpc();
+        await buy(tokenParams);
         expect.fail("Should have failed");
       } catch (error) {
         expect(error.message);
       }
     });
 
+    it("should fail when fewer tokens than min_tokens_out would be minted", async () => {
+      const tokenParams = {
+        name: "Slippage Token",
+        symbol: "SLIP",
+        decimals: 9,
+        uri: "https://example.com",
+        supply: new anchor.BN(1_000_000_000),
+        maxSupply: new anchor.BN(5_000_000_000),
+        fixedSupply: false,
+        curveType: { constantProduct: {} },
+        metadataStandard: { metaplex: {} },
+      };
+      try {
+        await buy(tokenParams, trader1, { minTokensOut: tokenParams.supply });
+        expect.fail("Should have failed");
+      } catch (error) {
+        expect(error.message).to.include("Slippage exceeded");
+      }
+    });
+
     it("should allow trader to buy multiple different tokens", async () => {
       // First token purchase
       const tokenParams1 = {
@@ -648,6 +1204,10 @@ describe("xdegen-demo", () => {
         decimals: 9,
         uri: "https://example.com/first",
         supply: new anchor.BN(1_000_000_000),
+        maxSupply: new anchor.BN(5_000_000_000),
+        fixedSupply: false,
+        curveType: { constantProduct: {} },
+        metadataStandard: { metaplex: {} },
       };
       const amount1 = 500_000_000;
       const newMint1 = anchor.web3.Keypair.generate();
@@ -656,25 +1216,7 @@ describe("xdegen-demo", () => {
         trader1.publicKey
       );
 
-      const metadata1 = PublicKey.findProgramAddressSync(
-        [Buffer.from("metadata"), METADATA_PROGRAM_ID.toBuffer(), newMint1.publicKey.toBuffer()],
-        METADATA_PROGRAM_ID
-      )[0];
-
-      await program.methods.buy(tokenParams1, new anchor.BN(amount1)).accountsPartial({
-        sessionToken: null,
-        trader: trader1.publicKey,
-        config: getConfigPDA(),
-        mint: newMint1.publicKey,
-        traderMintAta: traderMintAta1,
-        metadata: metadata1,
-        xdegenMint,
-        tokenRecord: getTokenRecordPDA(newMint1.publicKey, trader1.publicKey),
-        vault: getVaultPDA(),
-        traderXdegenAta: trader1XdegenAta,
-        tokenProgram: TOKEN_PROGRAM_ID,
-        systemProgram: SystemProgram.programId,
-      }).signers([trader1, newMint1]).rpc();
+      await buy(tokenParams1, trader1, { mint: newMint1, amount: amount1 });
 
       // Second token purchase
       const tokenParams2 = {
@@ -683,6 +1225,10 @@ describe("xdegen-demo", () => {
         decimals: 9,
         uri: "https://example.com/second",
         supply: new anchor.BN(2_000_000_000),
+        maxSupply: new anchor.BN(5_000_000_000),
+        fixedSupply: false,
+        curveType: { linear: {} },
+        metadataStandard: { metaplex: {} },
       };
 
       const amount = 1_000_000_000;
@@ -692,25 +1238,7 @@ describe("xdegen-demo", () => {
         trader1.publicKey
       );
 
-      const metadata2 = PublicKey.findProgramAddressSync(
-        [Buffer.from("metadata"), METADATA_PROGRAM_ID.toBuffer(), newMint2.publicKey.toBuffer()],
-        METADATA_PROGRAM_ID
-      )[0];
-
-      await program.methods.buy(tokenParams2, new anchor.BN(amount)).accountsPartial({
-        sessionToken: null,
-        trader: trader1.publicKey,
-        config: getConfigPDA(),
-        mint: newMint2.publicKey,
-        traderMintAta: traderMintAta2,
-        tokenRecord: getTokenRecordPDA(newMint2.publicKey, trader1.publicKey),
-        metadata: metadata2,
-        xdegenMint,
-        vault: getVaultPDA(),
-        traderXdegenAta: trader1XdegenAta,
-        tokenProgram: TOKEN_PROGRAM_ID,
-        systemProgram: SystemProgram.programId,
-      }).signers([trader1, newMint2]).rpc();
+      await buy(tokenParams2, trader1, { mint: newMint2, amount });
 
       // Verify both tokens have different mint addresses
       expect(newMint1.publicKey.toBase58()).to.not.equal(newMint2.publicKey.toBase58());
@@ -719,14 +1247,181 @@ describe("xdegen-demo", () => {
       const traderMintAccount1 = await getAccount(anchor.getProvider().connection, traderMintAta1);
       const traderMintAccount2 = await getAccount(anchor.getProvider().connection, traderMintAta2);
 
-      expect(traderMintAccount1.amount).to.equal(BigInt(tokenParams1.supply.toString()));
-      expect(traderMintAccount2.amount).to.equal(BigInt(tokenParams2.supply.toString()));
+      const bondingCurve1 = await program.account.bondingCurve.fetch(getBondingCurvePDA(newMint1.publicKey));
+      const bondingCurve2 = await program.account.bondingCurve.fetch(getBondingCurvePDA(newMint2.publicKey));
+      expect(bondingCurve1.curveType).to.have.property("constantProduct");
+      expect(bondingCurve2.curveType).to.have.property("linear");
+
+      expect(traderMintAccount1.amount).to.equal(BigInt(bondingCurve1.tokensSold.toString()));
+      expect(traderMintAccount2.amount).to.equal(BigInt(bondingCurve2.tokensSold.toString()));
 
       // Verify config was updated correctly
       const configAfter = await program.account.config.fetch(getConfigPDA());
       expect(configAfter.totalTrades.toNumber()).to.equal(3);
       expect(configAfter.totalBuys.toNumber()).to.equal(3);
     });
+
+    it("should create a Token-2022 token with the metadata extension", async () => {
+      const tokenParams = {
+        name: "Extension Token",
+        symbol: "EXT",
+        decimals: 6,
+        uri: "https://example.com/ext",
+        supply: new anchor.BN(1_000_000_000),
+        maxSupply: new anchor.BN(5_000_000_000),
+        fixedSupply: false,
+        curveType: { constantProduct: {} },
+        metadataStandard: { token2022: {} },
+      };
+      const traderMintAta = await getAssociatedTokenAddress(
+        extMint.publicKey,
+        trader1.publicKey,
+        false,
+        TOKEN_2022_PROGRAM_ID
+      );
+
+      await buy(tokenParams, trader1, { mint: extMint });
+
+      const mintInfo = await getMint(connection, extMint.publicKey, "confirmed", TOKEN_2022_PROGRAM_ID);
+      expect(mintInfo.decimals).to.equal(6);
+      expect(mintInfo.mintAuthority.toBase58()).to.equal(getMintAuthorityPDA(extMint.publicKey).toBase58());
+
+      const tokenMetadata = await getTokenMetadata(connection, extMint.publicKey);
+      expect(tokenMetadata.name).to.equal(tokenParams.name);
+      expect(tokenMetadata.symbol).to.equal(tokenParams.symbol);
+      expect(tokenMetadata.uri).to.equal(tokenParams.uri);
+
+      const tokenRecord = await program.account.tokenRecord.fetch(getTokenRecordPDA(extMint.publicKey, trader1.publicKey));
+      expect(tokenRecord.metadata.standard).to.deep.equal({ token2022: {} });
+
+      const traderMintAccount = await getAccount(connection, traderMintAta, "confirmed", TOKEN_2022_PROGRAM_ID);
+      expect(traderMintAccount.amount > BigInt(0)).to.be.true;
+    });
+
+    it("should fail when the mint program does not match the metadata standard", async () => {
+      const tokenParams = {
+        name: "Mismatch Token",
+        symbol: "MM",
+        decimals: 9,
+        uri: "https://example.com/mismatch",
+        supply: new anchor.BN(1_000_000_000),
+        maxSupply: new anchor.BN(5_000_000_000),
+        fixedSupply: false,
+        curveType: { constantProduct: {} },
+        metadataStandard: { metaplex: {} },
+      };
+      try {
+        await buy(tokenParams, trader1, { mintTokenProgram: TOKEN_2022_PROGRAM_ID });
+        expect.fail("Should have failed");
+      } catch (error) {
+        expect(error.message).to.include("Invalid token program");
+      }
+    });
+
+    const invalidParams: [string, object, string][] = [
+      ["a name over 60 bytes", { name: "N".repeat(61) }, "Name too long"],
+      ["a symbol over 32 bytes", { symbol: "S".repeat(33) }, "Symbol too long"],
+      ["a lowercase symbol", { symbol: "Bad" }, "Invalid symbol"],
+      ["a uri over 250 bytes", { uri: "https://example.com/" + "u".repeat(231) }, "Uri too long"],
+      ["a uri outside the scheme allowlist", { uri: "http://example.com" }, "Invalid uri"],
+      ["a supply under one whole token", { supply: new anchor.BN(999_999_999) }, "Invalid supply"],
+      ["a max supply over the cap for its decimals", { decimals: 0, maxSupply: new anchor.BN(1_000_000_000_001) }, "Invalid supply"],
+    ];
+
+    for (const [label, overrides, message] of invalidParams) {
+      it(`should fail with ${label}`, async () => {
+        const tokenParams = {
+          name: "Invalid Token",
+          symbol: "INV",
+          decimals: 9,
+          uri: "https://example.com/invalid",
+          supply: new anchor.BN(1_000_000_000),
+          maxSupply: new anchor.BN(5_000_000_000),
+          fixedSupply: false,
+          curveType: { constantProduct: {} },
+          metadataStandard: { metaplex: {} },
+          ...overrides,
+        };
+        try {
+          await buy(tokenParams);
+          expect.fail("Should have failed");
+        } catch (error) {
+          expect(error.message).to.include(message);
+        }
+      });
+    }
+  });
+
+  describe("Pause", () => {
+    const PAUSE_CLAIM = 1 << 3;
+
+    it("should reject a paused instruction until it is unpaused", async () => {
+      const signature = await program.methods.setPause(PAUSE_CLAIM, true).accountsPartial({
+        authority: admin.publicKey,
+        config: getConfigPDA(),
+      }).signers([admin]).rpc();
+      const event = (await getCpiEvents(signature)).find((e) => e.name === "pauseUpdated");
+      expect(event.data.oldPaused).to.equal(0);
+      expect(event.data.newPaused).to.equal(PAUSE_CLAIM);
+
+      try {
+        await program.methods.claim().accountsPartial({
+          sessionToken: null,
+          claimer: trader1.publicKey,
+          config: getConfigPDA(),
+          xdegenMint,
+          vault: getVaultPDA(),
+          claimerXdegenAta: trader1XdegenAta,
+          tokenProgram: TOKEN_PROGRAM_ID,
+          systemProgram: SystemProgram.programId,
+        }).signers([trader1]).rpc();
+        expect.fail("Should have failed");
+      } catch (error) {
+        expect(error.message).to.include("Program paused");
+      }
+
+      await program.methods.setPause(PAUSE_CLAIM, false).accountsPartial({
+        authority: admin.publicKey,
+        config: getConfigPDA(),
+      }).signers([admin]).rpc();
+
+      const configAccount = await program.account.config.fetch(getConfigPDA());
+      expect(configAccount.paused).to.equal(0);
+    });
+
+    it("should let a dedicated pauser toggle flags", async () => {
+      const ROLE_PAUSER = 4;
+      await program.methods.setRoles(trader2.publicKey, ROLE_PAUSER, true).accountsPartial({
+        admin: admin.publicKey,
+        config: getConfigPDA(),
+      }).signers([admin]).rpc();
+
+      await program.methods.setPause(PAUSE_CLAIM, true).accountsPartial({
+        authority: trader2.publicKey,
+        config: getConfigPDA(),
+      }).signers([trader2]).rpc();
+      await program.methods.setPause(PAUSE_CLAIM, false).accountsPartial({
+        authority: trader2.publicKey,
+        config: getConfigPDA(),
+      }).signers([trader2]).rpc();
+
+      await program.methods.setRoles(trader2.publicKey, ROLE_PAUSER, false).accountsPartial({
+        admin: admin.publicKey,
+        config: getConfigPDA(),
+      }).signers([admin]).rpc();
+    });
+
+    it("should fail with unauthorized", async () => {
+      try {
+        await program.methods.setPause(PAUSE_CLAIM, true).accountsPartial({
+          authority: trader1.publicKey,
+          config: getConfigPDA(),
+        }).signers([trader1]).rpc();
+        expect.fail("Should have failed");
+      } catch (error) {
+        expect(error.message).to.include("Missing role");
+      }
+    });
   });
 
   describe("Claim", () => {
@@ -749,6 +1444,38 @@ describe("xdegen-demo", () => {
       expect(configAfter.totalClaimed.toNumber()).to.eq(configBefore.claimAmount.toNumber());
     });
 
+    it("should record the claim for the claimer", async () => {
+      const [claimRecordPDA] = PublicKey.findProgramAddressSync(
+        [Buffer.from("claim_record"), trader1.publicKey.toBuffer()],
+        program.programId
+      );
+      const configAccount = await program.account.config.fetch(getConfigPDA());
+      const claimRecord = await program.account.claimRecord.fetch(claimRecordPDA);
+
+      expect(claimRecord.claimer.toBase58()).to.equal(trader1.publicKey.toBase58());
+      expect(claimRecord.claimCount.toNumber()).to.equal(1);
+      expect(claimRecord.totalClaimed.toString()).to.equal(configAccount.claimAmount.toString());
+      expect(claimRecord.lastClaimedAt.toNumber()).to.be.greaterThan(0);
+    });
+
+    it("should fail while the cooldown is active", async () => {
+      try {
+        await program.methods.claim().accountsPartial({
+          sessionToken: null,
+          claimer: trader1.publicKey,
+          config: getConfigPDA(),
+          xdegenMint,
+          vault: getVaultPDA(),
+          claimerXdegenAta: trader1XdegenAta,
+          tokenProgram: TOKEN_PROGRAM_ID,
+          systemProgram: SystemProgram.programId,
+        }).signers([trader1]).rpc();
+        expect.fail("Should have failed");
+      } catch (error) {
+        expect(error.message).to.include("Claim cooldown active");
+      }
+    });
+
     it("should fail with insufficient funds in vault", async () => {
       const amount = 1000_000_000_000; // more than available in vault
       try {
@@ -772,10 +1499,10 @@ describe("xdegen-demo", () => {
 
   describe("MintToken", () => {
     it("should mint token successfully", async () => {
-      const buyAmount = 1_000_000_000; // allowed amount
-      const mintAmount = 500_000_000;
+      const mintAmount = 100_000_000;
       const configBefore = await program.account.config.fetch(getConfigPDA());
-      const vaultBefore = await getAccount(anchor.getProvider().connection, getVaultPDA());
+      const reserveBefore = await getAccount(anchor.getProvider().connection, getReservePDA(newMint.publicKey));
+      const curveBefore = await program.account.bondingCurve.fetch(getBondingCurvePDA(newMint.publicKey));
       const traderMintAta = await getAssociatedTokenAddress(
         newMint.publicKey,
         trader1.publicKey
@@ -788,32 +1515,24 @@ describe("xdegen-demo", () => {
 
       const buyerMintBefore = await getAccount(anchor.getProvider().connection, traderMintAta);
 
-      await program.methods.mintToken(new anchor.BN(buyAmount), new anchor.BN(mintAmount)).accountsPartial({
-        sessionToken: null,
-        trader: trader1.publicKey,
-        config: getConfigPDA(),
-        mint: newMint.publicKey,
-        xdegenMint,
-        vault: getVaultPDA(),
-        traderXdegenAta: trader1XdegenAta,
-        traderMintAta: traderMintAta,
-        tokenProgram: TOKEN_PROGRAM_ID,
-        systemProgram: SystemProgram.programId,
-      }).signers([trader1]).rpc();
+      await mintMore(newMint.publicKey, mintAmount);
 
       const configAfter = await program.account.config.fetch(getConfigPDA());
-      const vaultAfter = await getAccount(anchor.getProvider().connection, getVaultPDA());
+      const reserveAfter = await getAccount(anchor.getProvider().connection, getReservePDA(newMint.publicKey));
+      const curveAfter = await program.account.bondingCurve.fetch(getBondingCurvePDA(newMint.publicKey));
       const buyerMintAfter = await getAccount(anchor.getProvider().connection, traderMintAta);
+      const cost = curveAfter.realXdegenReserves.sub(curveBefore.realXdegenReserves);
 
       expect(configAfter.totalTrades.toNumber()).to.equal(configBefore.totalTrades.toNumber() + 1);
       expect(configAfter.totalBuys.toNumber()).to.equal(configBefore.totalBuys.toNumber() + 1);
-      expect(vaultAfter.amount - vaultBefore.amount).to.equal(BigInt(buyAmount));
+      expect(cost.toNumber()).to.be.greaterThan(0);
+      expect(reserveAfter.amount - reserveBefore.amount).to.equal(BigInt(cost.toString()));
+      expect(configAfter.totalReserves.sub(configBefore.totalReserves).toString()).to.equal(cost.toString());
       expect(buyerMintAfter.amount - buyerMintBefore.amount).to.equal(BigInt(mintAmount));
     });
 
-    it("should fail with invalid buy amount", async () => {
-      const buyAmount = 123_456_789; // not in allowed amounts
-      const mintAmount = 500_000_000;
+    it("should fail with invalid mint amount", async () => {
+      const mintAmount = 0;
       try {
         const traderMintAta = await getAssociatedTokenAddress(
           newMint.publicKey,
@@ -825,62 +1544,38 @@ describe("xdegen-demo", () => {
           return
         }
 
-        await program.methods.mintToken(new anchor.BN(buyAmount), new anchor.BN(mintAmount)).accountsPartial({
-          sessionToken: null,
-          trader: trader1.publicKey,
-          config: getConfigPDA(),
-          mint: newMint.publicKey,
-          xdegenMint,
-          vault: getVaultPDA(),
-          traderXdegenAta: trader1XdegenAta,
-          traderMintAta: traderMintAta,
-          tokenProgram: TOKEN_PROGRAM_ID,
-          systemProgram: SystemProgram.programId,
-        }).signers([trader1]).rpc();
+        await mintMore(newMint.publicKey, mintAmount);
         expect.fail("Should have failed");
       } catch (error) {
-        console.log(error)
+        expect(error.message).to.include("Invalid amount");
       }
     });
 
-    it("should fail with invalid mint amount", async () => {
-      const buyAmount = 1_000_000_000; // allowed amount
-      const mintAmount = 0;
+    it("should fail when the cost exceeds max_xdegen_in", async () => {
+      const mintAmount = 100_000_000;
       try {
-        const traderMintAta = await getAssociatedTokenAddress(
-          newMint.publicKey,
-          trader1.publicKey
-        );
-
-        if (!connection.getAccountInfo(traderMintAta)) {
-          console.log('Token Account not found');
-          return
-        }
+        await mintMore(newMint.publicKey, mintAmount, { maxXdegenIn: new anchor.BN(1) });
+        expect.fail("Should have failed");
+      } catch (error) {
+        expect(error.message).to.include("Slippage exceeded");
+      }
+    });
 
-        await program.methods.mintToken(new anchor.BN(buyAmount), new anchor.BN(mintAmount)).accountsPartial({
-          sessionToken: null,
-          trader: trader1.publicKey,
-          config: getConfigPDA(),
-          mint: newMint.publicKey,
-          xdegenMint,
-          vault: getVaultPDA(),
-          traderXdegenAta: trader1XdegenAta,
-          traderMintAta: traderMintAta,
-          tokenProgram: TOKEN_PROGRAM_ID,
-          systemProgram: SystemProgram.programId,
-        }).signers([trader1]).rpc();
+    it("should fail when minting past the curve reserves", async () => {
+      // constant product curves can never sell their whole virtual token reserve
+      const mintAmount = 1_000_000_000;
+      try {
+        await mintMore(newMint.publicKey, mintAmount);
         expect.fail("Should have failed");
       } catch (error) {
-        console.log(error.message);
+        expect(error.message).to.include("Invalid amount");
       }
     });
 
     it("should fail with insufficient xdegen funds", async () => {
-      // Use up buyer's XDEGEN tokens first
-      const buyAmount = 5_000_000_000;
-      const mintAmount = 500_000_000;
+      // close to the whole reserve, far more XDEGEN than the trader holds
+      const mintAmount = 880_000_000;
 
-      // This should fail because buyer doesn't have enough XDEGEN tokens
       try {
         const traderMintAta = await getAssociatedTokenAddress(
           newMint.publicKey,
@@ -892,30 +1587,79 @@ describe("xdegen-demo", () => {
           return
         }
 
-        await program.methods.mintToken(new anchor.BN(buyAmount), new anchor.BN(mintAmount)).accountsPartial({
-          sessionToken: null,
-          trader: trader1.publicKey,
-          config: getConfigPDA(),
-          mint: newMint.publicKey,
-          xdegenMint,
-          vault: getVaultPDA(),
-          traderXdegenAta: traderXdegenAta,
-          traderMintAta: traderMintAta,
-          tokenProgram: TOKEN_PROGRAM_ID,
-          systemProgram: SystemProgram.programId,
-        }).signers([trader1]).rpc();
+        await mintMore(newMint.publicKey, mintAmount);
         expect.fail("Should have failed");
       } catch (error) {
-        expect(error.message);
+        expect(error.message).to.include("Insufficient funds");
+      }
+    });
+
+    const cappedParams = {
+      name: "Capped Token",
+      symbol: "CAP",
+      decimals: 9,
+      uri: "https://example.com/capped",
+      supply: new anchor.BN(1_000_000_000),
+      maxSupply: new anchor.BN(20_000_000),
+      fixedSupply: false,
+      curveType: { constantProduct: {} },
+      metadataStandard: { metaplex: {} },
+    };
+
+    it("should fail when minting past max_supply", async () => {
+      const cappedMint = Keypair.generate();
+      await buy(cappedParams, trader1, { mint: cappedMint });
+
+      const tokenRecord = await program.account.tokenRecord.fetch(getTokenRecordPDA(cappedMint.publicKey, trader1.publicKey));
+      expect(tokenRecord.maxSupply.toNumber()).to.equal(20_000_000);
+      expect(tokenRecord.fixedSupply).to.be.false;
+
+      try {
+        await mintMore(cappedMint.publicKey, 10_000_000);
+        expect.fail("Should have failed");
+      } catch (error) {
+        expect(error.message).to.include("Max supply exceeded");
+      }
+    });
+
+    it("should fail to mint a fixed supply token", async () => {
+      const fixedMint = Keypair.generate();
+      await buy({ ...cappedParams, symbol: "FIXED", maxSupply: new anchor.BN(5_000_000_000), fixedSupply: true }, trader1, { mint: fixedMint });
+
+      try {
+        await mintMore(fixedMint.publicKey, 1_000_000);
+        expect.fail("Should have failed");
+      } catch (error) {
+        expect(error.message).to.include("Fixed supply");
       }
     });
   });
 
   describe("Sell", () => {
+    function openHolderPosition(mint: PublicKey, holder: Keypair) {
+      return program.methods.openHolderPosition().accountsPartial({
+        holder: holder.publicKey,
+        mint,
+        holderMintAta: getAssociatedTokenAddressSync(mint, holder.publicKey),
+        bondingCurve: getBondingCurvePDA(mint),
+        holderPosition: getHolderPositionPDA(mint, holder.publicKey),
+        mintTokenProgram: TOKEN_PROGRAM_ID,
+        systemProgram: SystemProgram.programId,
+      }).signers([holder]).rpc();
+    }
+
+    it("should open a holder position for the creator", async () => {
+      await openHolderPosition(newMint.publicKey, trader1);
+
+      const position = await program.account.holderPosition.fetch(getHolderPositionPDA(newMint.publicKey, trader1.publicKey));
+      expect(position.holder.toBase58()).to.equal(trader1.publicKey.toBase58());
+      expect(position.mint.toBase58()).to.equal(newMint.publicKey.toBase58());
+    });
+
     it("should sell successfully", async () => {
-      const sellAmount = 500_000_000;
-      const burnAmount = 500_000_000;
+      const burnAmount = 50_000_000;
       const configBefore = await program.account.config.fetch(getConfigPDA());
+      const curveBefore = await program.account.bondingCurve.fetch(getBondingCurvePDA(newMint.publicKey));
 
       const traderMintAta = await getAssociatedTokenAddress(
         newMint.publicKey,
@@ -927,18 +1671,25 @@ describe("xdegen-demo", () => {
         return
       }
       const traderTokenBefore = await getAccount(anchor.getProvider().connection, traderMintAta);
+      const traderXdegenBefore = await getAccount(anchor.getProvider().connection, trader1XdegenAta);
 
-      await program.methods.sell(new anchor.BN(sellAmount), new anchor.BN(burnAmount)).accountsPartial({
-        sessionToken: null,
+      await program.methods.sell(new anchor.BN(burnAmount), new anchor.BN(0)).accountsPartial({
         trader: trader1.publicKey,
         config: getConfigPDA(),
-        vault: getVaultPDA(),
+        reserve: getReservePDA(newMint.publicKey),
+        feeVault: getFeeVaultPDA(),
+        traderReferral: getReferralPDA(trader1.publicKey),
+        referrerReferral: null,
         mint: newMint.publicKey,
         traderMint: traderMintAta,
         xdegenMint,
-        traderXdegenAta,
+        traderXdegenAta: trader1XdegenAta,
+        bondingCurve: getBondingCurvePDA(newMint.publicKey),
+        holderPosition: getHolderPositionPDA(newMint.publicKey, trader1.publicKey),
+        tokenRecord: getTokenRecordPDA(newMint.publicKey, trader1.publicKey),
         associatedTokenProgram: anchor.utils.token.ASSOCIATED_PROGRAM_ID,
         tokenProgram: TOKEN_PROGRAM_ID,
+        mintTokenProgram: TOKEN_PROGRAM_ID,
         systemProgram: SystemProgram.programId,
       }).signers([trader1]).rpc();
 
@@ -946,13 +1697,23 @@ describe("xdegen-demo", () => {
       expect(configAfter.totalTrades.toNumber()).to.equal(configBefore.totalTrades.toNumber() + 1);
       expect(configAfter.totalSells.toNumber()).to.equal(configBefore.totalSells.toNumber() + 1);
 
+      const curveAfter = await program.account.bondingCurve.fetch(getBondingCurvePDA(newMint.publicKey));
+      const payout = curveBefore.realXdegenReserves.sub(curveAfter.realXdegenReserves);
+      expect(curveBefore.tokensSold.sub(curveAfter.tokensSold).toNumber()).to.equal(burnAmount);
+
+      const protocolFee = payout.muln(configBefore.protocolFeeBps).divn(10_000);
+      const creatorFee = payout.muln(configBefore.creatorFeeBps).divn(10_000);
+      expect(configAfter.protocolFees.sub(configBefore.protocolFees).toString()).to.equal(protocolFee.toString());
+      expect(configAfter.creatorFees.sub(configBefore.creatorFees).toString()).to.equal(creatorFee.toString());
+
       const traderTokenAfter = await getAccount(anchor.getProvider().connection, traderMintAta);
+      const traderXdegenAfter = await getAccount(anchor.getProvider().connection, trader1XdegenAta);
       expect(traderTokenBefore.amount - traderTokenAfter.amount).to.equal(BigInt(burnAmount));
+      expect(traderXdegenAfter.amount - traderXdegenBefore.amount).to.equal(BigInt(payout.sub(protocolFee).sub(creatorFee).toString()));
     });
 
-    it("should fail with invalid sell amount", async () => {
-      const sellAmount = 123_456_789; // not allowed
-      const burnAmount = 500_000_000;
+    it("should fail with invalid burn amount", async () => {
+      const burnAmount = 0;
       try {
         const traderMintAta = await getAssociatedTokenAddress(
           newMint.publicKey,
@@ -963,88 +1724,1119 @@ describe("xdegen-demo", () => {
           console.log('Token Account not found');
           return
         }
-        await program.methods.sell(new anchor.BN(sellAmount), new anchor.BN(burnAmount)).accountsPartial({
-          sessionToken: null,
+        await program.methods.sell(new anchor.BN(burnAmount), new anchor.BN(0)).accountsPartial({
           trader: trader1.publicKey,
           config: getConfigPDA(),
-          vault: getVaultPDA(),
+          reserve: getReservePDA(newMint.publicKey),
+          feeVault: getFeeVaultPDA(),
+          traderReferral: getReferralPDA(trader1.publicKey),
+          referrerReferral: null,
           mint: newMint.publicKey,
           traderMint: traderMintAta,
           xdegenMint,
           traderXdegenAta: trader1XdegenAta,
+          bondingCurve: getBondingCurvePDA(newMint.publicKey),
+          holderPosition: getHolderPositionPDA(newMint.publicKey, trader1.publicKey),
+          tokenRecord: getTokenRecordPDA(newMint.publicKey, trader1.publicKey),
           associatedTokenProgram: anchor.utils.token.ASSOCIATED_PROGRAM_ID,
           tokenProgram: TOKEN_PROGRAM_ID,
+          mintTokenProgram: TOKEN_PROGRAM_ID,
           systemProgram: SystemProgram.programId,
         }).signers([trader1]).rpc();
-
         expect.fail("Should have failed");
       } catch (error) {
-        console.log(error.message);
+        expect(error.message).to.include("Invalid amount");
       }
     });
 
-    it("should fail with invalid burn amount", async () => {
-      const sellAmount = 500_000_000;
-      const burnAmount = 0;
+    it("should fail when the payout is below min_xdegen_out", async () => {
+      const burnAmount = 10_000_000;
       try {
         const traderMintAta = await getAssociatedTokenAddress(
           newMint.publicKey,
           trader1.publicKey
         );
 
-        if (!connection.getAccountInfo(traderMintAta)) {
-          console.log('Token Account not found');
-          return
-        }
-        await program.methods.sell(new anchor.BN(sellAmount), new anchor.BN(burnAmount)).accountsPartial({
-          sessionToken: null,
+        await program.methods.sell(new anchor.BN(burnAmount), MAX_U64).accountsPartial({
           trader: trader1.publicKey,
           config: getConfigPDA(),
-          vault: getVaultPDA(),
+          reserve: getReservePDA(newMint.publicKey),
+          feeVault: getFeeVaultPDA(),
+          traderReferral: getReferralPDA(trader1.publicKey),
+          referrerReferral: null,
           mint: newMint.publicKey,
           traderMint: traderMintAta,
           xdegenMint,
           traderXdegenAta: trader1XdegenAta,
+          bondingCurve: getBondingCurvePDA(newMint.publicKey),
+          holderPosition: getHolderPositionPDA(newMint.publicKey, trader1.publicKey),
+          tokenRecord: getTokenRecordPDA(newMint.publicKey, trader1.publicKey),
           associatedTokenProgram: anchor.utils.token.ASSOCIATED_PROGRAM_ID,
           tokenProgram: TOKEN_PROGRAM_ID,
+          mintTokenProgram: TOKEN_PROGRAM_ID,
           systemProgram: SystemProgram.programId,
         }).signers([trader1]).rpc();
         expect.fail("Should have failed");
       } catch (error) {
-        console.log(error.message);
+        expect(error.message).to.include("Slippage exceeded");
       }
     });
 
-    it("should fail with insufficient xdegen funds", async () => {
-      const sellAmount = 50_000_000_000; // more than trader has
-      const burnAmount = 500_000_000;
+    it("should fail when burning more than the curve has sold", async () => {
+      const burnAmount = 1_000_000_000;
       try {
         const traderMintAta = await getAssociatedTokenAddress(
           newMint.publicKey,
           trader1.publicKey
         );
 
-        if (!connection.getAccountInfo(traderMintAta)) {
-          console.log('Token Account not found');
-          return
-        }
-        await program.methods.sell(new anchor.BN(sellAmount), new anchor.BN(burnAmount)).accountsPartial({
-          sessionToken: null,
+        await program.methods.sell(new anchor.BN(burnAmount), new anchor.BN(0)).accountsPartial({
           trader: trader1.publicKey,
           config: getConfigPDA(),
-          vault: getVaultPDA(),
+          reserve: getReservePDA(newMint.publicKey),
+          feeVault: getFeeVaultPDA(),
+          traderReferral: getReferralPDA(trader1.publicKey),
+          referrerReferral: null,
           mint: newMint.publicKey,
           traderMint: traderMintAta,
           xdegenMint,
           traderXdegenAta: trader1XdegenAta,
+          bondingCurve: getBondingCurvePDA(newMint.publicKey),
+          holderPosition: getHolderPositionPDA(newMint.publicKey, trader1.publicKey),
+          tokenRecord: getTokenRecordPDA(newMint.publicKey, trader1.publicKey),
+          associatedTokenProgram: anchor.utils.token.ASSOCIATED_PROGRAM_ID,
+          tokenProgram: TOKEN_PROGRAM_ID,
+          mintTokenProgram: TOKEN_PROGRAM_ID,
+          systemProgram: SystemProgram.programId,
+        }).signers([trader1]).rpc();
+        expect.fail("Should have failed");
+      } catch (error) {
+        expect(error.message).to.include("Invalid amount");
+      }
+    });
+
+    it("should fail to open a position without holding the token", async () => {
+      await getOrCreateAssociatedTokenAccount(connection, trader2, newMint.publicKey, trader2.publicKey);
+
+      try {
+        await openHolderPosition(newMint.publicKey, trader2);
+        expect.fail("Should have failed");
+      } catch (error) {
+        expect(error.message).to.include("No tokens held");
+      }
+    });
+
+    it("should let a holder who received tokens by transfer sell them", async () => {
+      const amount = 10_000_000;
+      const trader2MintAta = getAssociatedTokenAddressSync(newMint.publicKey, trader2.publicKey);
+      await transfer(
+        connection,
+        trader1,
+        getAssociatedTokenAddressSync(newMint.publicKey, trader1.publicKey),
+        trader2MintAta,
+        trader1,
+        amount
+      );
+      await openHolderPosition(newMint.publicKey, trader2);
+
+      const recordBefore = await program.account.tokenRecord.fetch(getTokenRecordPDA(newMint.publicKey, trader1.publicKey));
+
+      const signature = await program.methods.sell(new anchor.BN(amount), new anchor.BN(0)).accountsPartial({
+        trader: trader2.publicKey,
+        config: getConfigPDA(),
+        reserve: getReservePDA(newMint.publicKey),
+        feeVault: getFeeVaultPDA(),
+        traderReferral: getReferralPDA(trader2.publicKey),
+        referrerReferral: null,
+        mint: newMint.publicKey,
+        traderMint: trader2MintAta,
+        xdegenMint,
+        traderXdegenAta: trader2XdegenAta,
+        bondingCurve: getBondingCurvePDA(newMint.publicKey),
+        holderPosition: getHolderPositionPDA(newMint.publicKey, trader2.publicKey),
+        tokenRecord: getTokenRecordPDA(newMint.publicKey, trader1.publicKey),
+        associatedTokenProgram: anchor.utils.token.ASSOCIATED_PROGRAM_ID,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        mintTokenProgram: TOKEN_PROGRAM_ID,
+        systemProgram: SystemProgram.programId,
+      }).signers([trader2]).rpc();
+
+      const event = (await getCpiEvents(signature)).find((e) => e.name === "tokensSold");
+      const position = await program.account.holderPosition.fetch(getHolderPositionPDA(newMint.publicKey, trader2.publicKey));
+      expect(position.tokensSold.toNumber()).to.equal(amount);
+      expect(position.xdegenReceived.toNumber()).to.equal(event.data.xdegenOut.toNumber());
+      expect((await getAccount(connection, trader2MintAta)).amount).to.equal(BigInt(0));
+      expect(event.data.tokenAccountBalance.toNumber()).to.equal(0);
+
+      const recordAfter = await program.account.tokenRecord.fetch(getTokenRecordPDA(newMint.publicKey, trader1.publicKey));
+      expect(event.data.balance.toString()).to.equal(recordAfter.balance.toString());
+      expect(recordAfter.creatorFees.toNumber()).to.equal(recordBefore.creatorFees.toNumber() + event.data.creatorFee.toNumber());
+      expect(recordAfter.balance.toNumber()).to.equal(recordBefore.balance.toNumber());
+    });
+
+    it("should let a holder sell from their token account without a position", async () => {
+      const amount = 10_000_000;
+      const trader2MintAta = getAssociatedTokenAddressSync(newMint.publicKey, trader2.publicKey);
+      await transfer(
+        connection,
+        trader1,
+        getAssociatedTokenAddressSync(newMint.publicKey, trader1.publicKey),
+        trader2MintAta,
+        trader1,
+        amount
+      );
+      const positionBefore = await program.account.holderPosition.fetch(getHolderPositionPDA(newMint.publicKey, trader2.publicKey));
+
+      await program.methods.sell(new anchor.BN(amount), new anchor.BN(0)).accountsPartial({
+        trader: trader2.publicKey,
+        config: getConfigPDA(),
+        reserve: getReservePDA(newMint.publicKey),
+        feeVault: getFeeVaultPDA(),
+        traderReferral: getReferralPDA(trader2.publicKey),
+        referrerReferral: null,
+        mint: newMint.publicKey,
+        traderMint: trader2MintAta,
+        xdegenMint,
+        traderXdegenAta: trader2XdegenAta,
+        bondingCurve: getBondingCurvePDA(newMint.publicKey),
+        holderPosition: null,
+        tokenRecord: getTokenRecordPDA(newMint.publicKey, trader1.publicKey),
+        associatedTokenProgram: anchor.utils.token.ASSOCIATED_PROGRAM_ID,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        mintTokenProgram: TOKEN_PROGRAM_ID,
+        systemProgram: SystemProgram.programId,
+      }).signers([trader2]).rpc();
+
+      expect((await getAccount(connection, trader2MintAta)).amount).to.equal(BigInt(0));
+      const positionAfter = await program.account.holderPosition.fetch(getHolderPositionPDA(newMint.publicKey, trader2.publicKey));
+      expect(positionAfter.sellCount.toNumber()).to.equal(positionBefore.sellCount.toNumber());
+    });
+
+    it("should fail to sell from another wallet's token account", async () => {
+      try {
+        await program.methods.sell(new anchor.BN(1_000_000), new anchor.BN(0)).accountsPartial({
+          trader: trader2.publicKey,
+          config: getConfigPDA(),
+          reserve: getReservePDA(newMint.publicKey),
+          feeVault: getFeeVaultPDA(),
+          traderReferral: getReferralPDA(trader2.publicKey),
+          referrerReferral: null,
+          mint: newMint.publicKey,
+          traderMint: getAssociatedTokenAddressSync(newMint.publicKey, trader1.publicKey),
+          xdegenMint,
+          traderXdegenAta: trader2XdegenAta,
+          bondingCurve: getBondingCurvePDA(newMint.publicKey),
+          holderPosition: null,
+          tokenRecord: getTokenRecordPDA(newMint.publicKey, trader1.publicKey),
+          associatedTokenProgram: anchor.utils.token.ASSOCIATED_PROGRAM_ID,
+          tokenProgram: TOKEN_PROGRAM_ID,
+          mintTokenProgram: TOKEN_PROGRAM_ID,
+          systemProgram: SystemProgram.programId,
+        }).signers([trader2]).rpc();
+        expect.fail("Should have failed");
+      } catch (error) {
+        expect(error.message).to.include("ConstraintTokenOwner");
+      }
+    });
+
+    it("should not take a session key for a sell", async () => {
+      const sell = program.idl.instructions.find((ix) => ix.name === "sell");
+      expect(sell.accounts.map((account) => account.name)).to.not.include("sessionToken");
+    });
+  });
+
+  describe("Fees", () => {
+    it("should let the creator claim accrued creator fees", async () => {
+      const configBefore = await program.account.config.fetch(getConfigPDA());
+      const recordBefore = await program.account.tokenRecord.fetch(getTokenRecordPDA(newMint.publicKey, trader1.publicKey));
+      const traderXdegenBefore = await getAccount(anchor.getProvider().connection, trader1XdegenAta);
+      expect(recordBefore.creatorFees.toNumber()).to.be.greaterThan(0);
+
+      await program.methods.claimCreatorFees().accountsPartial({
+        creator: trader1.publicKey,
+        config: getConfigPDA(),
+        xdegenMint,
+        feeVault: getFeeVaultPDA(),
+        tokenRecord: getTokenRecordPDA(newMint.publicKey, trader1.publicKey),
+        creatorXdegenAta: trader1XdegenAta,
+        associatedTokenProgram: anchor.utils.token.ASSOCIATED_PROGRAM_ID,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        systemProgram: SystemProgram.programId,
+      }).signers([trader1]).rpc();
+
+      const configAfter = await program.account.config.fetch(getConfigPDA());
+      const recordAfter = await program.account.tokenRecord.fetch(getTokenRecordPDA(newMint.publicKey, trader1.publicKey));
+      const traderXdegenAfter = await getAccount(anchor.getProvider().connection, trader1XdegenAta);
+      expect(recordAfter.creatorFees.toNumber()).to.equal(0);
+      expect(configBefore.creatorFees.sub(configAfter.creatorFees).toString()).to.equal(recordBefore.creatorFees.toString());
+      expect(traderXdegenAfter.amount - traderXdegenBefore.amount).to.equal(BigInt(recordBefore.creatorFees.toString()));
+    });
+
+    it("should fail to claim creator fees twice", async () => {
+      try {
+        await program.methods.claimCreatorFees().accountsPartial({
+          creator: trader1.publicKey,
+          config: getConfigPDA(),
+          xdegenMint,
+          feeVault: getFeeVaultPDA(),
+          tokenRecord: getTokenRecordPDA(newMint.publicKey, trader1.publicKey),
+          creatorXdegenAta: trader1XdegenAta,
+          associatedTokenProgram: anchor.utils.token.ASSOCIATED_PROGRAM_ID,
+          tokenProgram: TOKEN_PROGRAM_ID,
+          systemProgram: SystemProgram.programId,
+        }).signers([trader1]).rpc();
+        expect.fail("Should have failed");
+      } catch (error) {
+        expect(error.message).to.include("No fees accrued");
+      }
+    });
+
+    it("should fail to collect protocol fees with unauthorized", async () => {
+      try {
+        await program.methods.collectProtocolFees().accountsPartial({
+          authority: trader1.publicKey,
+          config: getConfigPDA(),
+          xdegenMint,
+          feeVault: getFeeVaultPDA(),
+          authorityXdegenAta: trader1XdegenAta,
           associatedTokenProgram: anchor.utils.token.ASSOCIATED_PROGRAM_ID,
           tokenProgram: TOKEN_PROGRAM_ID,
           systemProgram: SystemProgram.programId,
         }).signers([trader1]).rpc();
         expect.fail("Should have failed");
       } catch (error) {
-        console.log(error.message);
+        expect(error.message).to.include("Missing role");
+      }
+    });
+
+    it("should let the admin collect protocol fees", async () => {
+      const configBefore = await program.account.config.fetch(getConfigPDA());
+      const adminBefore = await getAccount(anchor.getProvider().connection, adminXdegenATA);
+
+      await program.methods.collectProtocolFees().accountsPartial({
+        authority: admin.publicKey,
+        config: getConfigPDA(),
+        xdegenMint,
+        feeVault: getFeeVaultPDA(),
+        authorityXdegenAta: adminXdegenATA,
+        associatedTokenProgram: anchor.utils.token.ASSOCIATED_PROGRAM_ID,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        systemProgram: SystemProgram.programId,
+      }).signers([admin]).rpc();
+
+      const configAfter = await program.account.config.fetch(getConfigPDA());
+      const adminAfter = await getAccount(anchor.getProvider().connection, adminXdegenATA);
+      expect(configAfter.protocolFees.toNumber()).to.equal(0);
+      expect(adminAfter.amount - adminBefore.amount).to.equal(BigInt(configBefore.protocolFees.toString()));
+    });
+  });
+
+  describe("Referral", () => {
+    const mintAmount = 10_000_000;
+
+    it("should create referral accounts", async () => {
+      for (const owner of [trader1, trader2]) {
+        await program.methods.createReferralAccount().accountsPartial({
+          owner: owner.publicKey,
+          referralAccount: getReferralPDA(owner.publicKey),
+          systemProgram: SystemProgram.programId,
+        }).signers([owner]).rpc();
+      }
+
+      const referral = await program.account.referralAccount.fetch(getReferralPDA(trader1.publicKey));
+      expect(referral.owner.toBase58()).to.equal(trader1.publicKey.toBase58());
+      expect(referral.referrer).to.be.null;
+    });
+
+    it("should reject self referral", async () => {
+      try {
+        await mintMore(newMint.publicKey, mintAmount, { referrer: trader1.publicKey });
+        expect.fail("Should have failed");
+      } catch (error) {
+        expect(error.message).to.include("Self referral");
+      }
+    });
+
+    it("should link the trader and pay the referrer a share of the protocol fee", async () => {
+      const configBefore = await program.account.config.fetch(getConfigPDA());
+      await mintMore(newMint.publicKey, mintAmount, { referrer: trader2.publicKey });
+      const configAfter = await program.account.config.fetch(getConfigPDA());
+
+      const traderReferral = await program.account.referralAccount.fetch(getReferralPDA(trader1.publicKey));
+      const referrerReferral = await program.account.referralAccount.fetch(getReferralPDA(trader2.publicKey));
+      expect(traderReferral.referrer.toBase58()).to.equal(trader2.publicKey.toBase58());
+      expect(referrerReferral.referralCount.toNumber()).to.equal(1);
+      expect(referrerReferral.rewards.toNumber()).to.be.greaterThan(0);
+      expect(configAfter.referralFees.sub(configBefore.referralFees).toString()).to.equal(referrerReferral.rewards.toString());
+    });
+
+    it("should reject a trade that leaves out the bound referrer", async () => {
+      try {
+        await mintMore(newMint.publicKey, mintAmount);
+        expect.fail("Should have failed");
+      } catch (error) {
+        expect(error.message).to.include("Missing referrer");
+      }
+    });
+
+    it("should reject a referral loop", async () => {
+      const tokenParams = {
+        name: "Loop Token",
+        symbol: "LT",
+        decimals: 9,
+        uri: "https://example.com/loop",
+        supply: new anchor.BN(1_000_000_000),
+        maxSupply: new anchor.BN(5_000_000_000),
+        fixedSupply: false,
+        curveType: { constantProduct: {} },
+        metadataStandard: { metaplex: {} },
+      };
+      try {
+        await buy(tokenParams, trader2, { referrer: trader1.publicKey });
+        expect.fail("Should have failed");
+      } catch (error) {
+        expect(error.message).to.include("Referral loop");
+      }
+    });
+
+    it("should let the referrer claim rewards", async () => {
+      const referralBefore = await program.account.referralAccount.fetch(getReferralPDA(trader2.publicKey));
+      const trader2Before = await getAccount(anchor.getProvider().connection, trader2XdegenAta);
+
+      await program.methods.claimReferralRewards().accountsPartial({
+        referrer: trader2.publicKey,
+        config: getConfigPDA(),
+        xdegenMint,
+        feeVault: getFeeVaultPDA(),
+        referralAccount: getReferralPDA(trader2.publicKey),
+        referrerXdegenAta: trader2XdegenAta,
+        associatedTokenProgram: anchor.utils.token.ASSOCIATED_PROGRAM_ID,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        systemProgram: SystemProgram.programId,
+      }).signers([trader2]).rpc();
+
+      const referralAfter = await program.account.referralAccount.fetch(getReferralPDA(trader2.publicKey));
+      const trader2After = await getAccount(anchor.getProvider().connection, trader2XdegenAta);
+      expect(referralAfter.rewards.toNumber()).to.equal(0);
+      expect(referralAfter.totalRewards.toString()).to.equal(referralBefore.totalRewards.toString());
+      expect(trader2After.amount - trader2Before.amount).to.equal(BigInt(referralBefore.rewards.toString()));
+    });
+  });
+
+  describe("UpdateTokenMetadata", () => {
+    function getMetadataPDA(mint: PublicKey) {
+      return PublicKey.findProgramAddressSync(
+        [Buffer.from("metadata"), METADATA_PROGRAM_ID.toBuffer(), mint.toBuffer()],
+        METADATA_PROGRAM_ID
+      )[0];
+    }
+
+    function updateMetadata(params, signer: Keypair = trader1) {
+      return program.methods.updateTokenMetadata({ name: null, symbol: null, uri: null, lock: false, ...params }).accountsPartial({
+        sessionToken: null,
+        trader: signer.publicKey,
+        tokenRecord: getTokenRecordPDA(newMint.publicKey, trader1.publicKey),
+        mint: newMint.publicKey,
+        mintAuthority: getMintAuthorityPDA(newMint.publicKey),
+        bondingCurve: getBondingCurvePDA(newMint.publicKey),
+        newSymbolRecord: null,
+        oldSymbolRecord: null,
+        metadata: getMetadataPDA(newMint.publicKey),
+        tokenMetadataProgram: METADATA_PROGRAM_ID,
+        mintTokenProgram: TOKEN_PROGRAM_ID,
+        systemProgram: SystemProgram.programId,
+      }).signers([signer]).rpc();
+    }
+
+    it("should update the Metaplex metadata and the token record together", async () => {
+      await updateMetadata({ uri: "https://example.com/v2" });
+
+      const tokenRecord = await program.account.tokenRecord.fetch(getTokenRecordPDA(newMint.publicKey, trader1.publicKey));
+      expect(tokenRecord.metadata.uri).to.equal("https://example.com/v2");
+      expect(tokenRecord.metadata.isMutable).to.be.true;
+
+      const metadataAccount = await connection.getAccountInfo(getMetadataPDA(newMint.publicKey));
+      expect(metadataAccount.data.toString()).to.include("https://example.com/v2");
+    });
+
+    it("should update Token-2022 metadata in the mint", async () => {
+      await program.methods.updateTokenMetadata({ name: "Extension Token v2", symbol: null, uri: null, lock: false }).accountsPartial({
+        sessionToken: null,
+        trader: trader1.publicKey,
+        tokenRecord: getTokenRecordPDA(extMint.publicKey, trader1.publicKey),
+        mint: extMint.publicKey,
+        mintAuthority: getMintAuthorityPDA(extMint.publicKey),
+        bondingCurve: getBondingCurvePDA(extMint.publicKey),
+        newSymbolRecord: null,
+        oldSymbolRecord: null,
+        metadata: null,
+        tokenMetadataProgram: null,
+        mintTokenProgram: TOKEN_2022_PROGRAM_ID,
+        systemProgram: SystemProgram.programId,
+      }).signers([trader1]).rpc();
+
+      const tokenMetadata = await getTokenMetadata(connection, extMint.publicKey);
+      expect(tokenMetadata.name).to.equal("Extension Token v2");
+      const tokenRecord = await program.account.tokenRecord.fetch(getTokenRecordPDA(extMint.publicKey, trader1.publicKey));
+      expect(tokenRecord.metadata.name).to.equal("Extension Token v2");
+    });
+
+    it("should fail with a name over 60 bytes", async () => {
+      try {
+        await updateMetadata({ name: "N".repeat(61) });
+        expect.fail("Should have failed");
+      } catch (error) {
+        expect(error.message).to.include("Name too long");
       }
     });
+
+    it("should fail with unauthorized", async () => {
+      try {
+        await updateMetadata({ uri: "https://example.com/hijack" }, trader2);
+        expect.fail("Should have failed");
+      } catch (error) {
+        expect(error.message).to.include("Unauthorized");
+      }
+    });
+
+    it("should lock the metadata for good", async () => {
+      await updateMetadata({ lock: true });
+
+      const tokenRecord = await program.account.tokenRecord.fetch(getTokenRecordPDA(newMint.publicKey, trader1.publicKey));
+      expect(tokenRecord.metadata.isMutable).to.be.false;
+
+      try {
+        await updateMetadata({ uri: "https://example.com/v3" });
+        expect.fail("Should have failed");
+      } catch (error) {
+        expect(error.message).to.include("Metadata immutable");
+      }
+    });
+  });
+
+  describe("SymbolRegistry", () => {
+    const registryParams = {
+      name: "Registry Token",
+      symbol: "TT",
+      decimals: 9,
+      uri: "https://example.com/registry",
+      supply: new anchor.BN(1_000_000_000),
+      maxSupply: new anchor.BN(5_000_000_000),
+      fixedSupply: false,
+      curveType: { constantProduct: {} },
+      metadataStandard: { metaplex: {} },
+    };
+
+    it("should register the symbol to the mint created by buy", async () => {
+      const symbolRecord = await program.account.symbolRecord.fetch(getSymbolRecordPDA("TT"));
+      expect(symbolRecord.symbol).to.equal("TT");
+      expect(symbolRecord.mint.toBase58()).to.equal(newMint.publicKey.toBase58());
+    });
+
+    it("should fail to buy a token with a taken symbol", async () => {
+      try {
+        await buy(registryParams, trader1, { referrer: trader2.publicKey });
+        expect.fail("Should have failed");
+      } catch (error) {
+        expect(error.message).to.include("Symbol taken");
+      }
+    });
+
+    it("should fail to reserve a symbol with unauthorized", async () => {
+      try {
+        await program.methods.reserveSymbol("XDEGEN").accountsPartial({
+          authority: trader1.publicKey,
+          config: getConfigPDA(),
+          symbolRecord: getSymbolRecordPDA("XDEGEN"),
+        }).signers([trader1]).rpc();
+        expect.fail("Should have failed");
+      } catch (error) {
+        expect(error.message).to.include("Missing role");
+      }
+    });
+
+    it("should reserve and release a symbol", async () => {
+      const signature = await program.methods.reserveSymbol("XDEGEN").accountsPartial({
+        authority: admin.publicKey,
+        config: getConfigPDA(),
+        symbolRecord: getSymbolRecordPDA("XDEGEN"),
+      }).signers([admin]).rpc();
+
+      const event = (await getCpiEvents(signature)).find((e) => e.name === "symbolReserved");
+      expect(event.data.symbol).to.equal("XDEGEN");
+      const symbolRecord = await program.account.symbolRecord.fetch(getSymbolRecordPDA("XDEGEN"));
+      expect(symbolRecord.mint).to.be.null;
+
+      try {
+        await buy({ ...registryParams, symbol: "XDEGEN" }, trader1, { referrer: trader2.publicKey });
+        expect.fail("Should have failed");
+      } catch (error) {
+        expect(error.message).to.include("Symbol taken");
+      }
+
+      await program.methods.releaseSymbol().accountsPartial({
+        authority: admin.publicKey,
+        config: getConfigPDA(),
+        symbolRecord: getSymbolRecordPDA("XDEGEN"),
+      }).signers([admin]).rpc();
+      expect(await connection.getAccountInfo(getSymbolRecordPDA("XDEGEN"))).to.be.null;
+
+      const xdegenMintCopy = Keypair.generate();
+      await buy({ ...registryParams, symbol: "XDEGEN" }, trader1, { mint: xdegenMintCopy, referrer: trader2.publicKey });
+      const registered = await program.account.symbolRecord.fetch(getSymbolRecordPDA("XDEGEN"));
+      expect(registered.mint.toBase58()).to.equal(xdegenMintCopy.publicKey.toBase58());
+    });
+
+    it("should move the registry entry when the symbol is updated", async () => {
+      await program.methods.updateTokenMetadata({ name: null, symbol: "EXT2", uri: null, lock: false }).accountsPartial({
+        sessionToken: null,
+        trader: trader1.publicKey,
+        tokenRecord: getTokenRecordPDA(extMint.publicKey, trader1.publicKey),
+        mint: extMint.publicKey,
+        mintAuthority: getMintAuthorityPDA(extMint.publicKey),
+        bondingCurve: getBondingCurvePDA(extMint.publicKey),
+        newSymbolRecord: getSymbolRecordPDA("EXT2"),
+        oldSymbolRecord: getSymbolRecordPDA("EXT"),
+        metadata: null,
+        tokenMetadataProgram: null,
+        mintTokenProgram: TOKEN_2022_PROGRAM_ID,
+        systemProgram: SystemProgram.programId,
+      }).signers([trader1]).rpc();
+
+      const symbolRecord = await program.account.symbolRecord.fetch(getSymbolRecordPDA("EXT2"));
+      expect(symbolRecord.mint.toBase58()).to.equal(extMint.publicKey.toBase58());
+      expect(await connection.getAccountInfo(getSymbolRecordPDA("EXT"))).to.be.null;
+    });
+
+    it("should reject registry accounts when the symbol does not change", async () => {
+      try {
+        await program.methods.updateTokenMetadata({ name: null, symbol: "EXT2", uri: null, lock: false }).accountsPartial({
+          sessionToken: null,
+          trader: trader1.publicKey,
+          tokenRecord: getTokenRecordPDA(extMint.publicKey, trader1.publicKey),
+          mint: extMint.publicKey,
+          mintAuthority: getMintAuthorityPDA(extMint.publicKey),
+          bondingCurve: getBondingCurvePDA(extMint.publicKey),
+          newSymbolRecord: getSymbolRecordPDA("EXT2"),
+          oldSymbolRecord: getSymbolRecordPDA("EXT2"),
+          metadata: null,
+          tokenMetadataProgram: null,
+          mintTokenProgram: TOKEN_2022_PROGRAM_ID,
+          systemProgram: SystemProgram.programId,
+        }).signers([trader1]).rpc();
+        expect.fail("Should have failed");
+      } catch (error) {
+        expect(error.message).to.include("Unexpected symbol record");
+      }
+    });
+  });
+
+  describe("CloseTokenRecord", () => {
+    const closeMint = Keypair.generate();
+    let closeMintAta: PublicKey;
+
+    function closeTokenRecord() {
+      return program.methods.closeTokenRecord().accountsPartial({
+        owner: trader1.publicKey,
+        tokenRecord: getTokenRecordPDA(closeMint.publicKey, trader1.publicKey),
+        mint: closeMint.publicKey,
+        ownerMintAta: closeMintAta,
+        mintTokenProgram: TOKEN_PROGRAM_ID,
+      }).signers([trader1]).rpc();
+    }
+
+    before(async () => {
+      const tokenParams = {
+        name: "Closing Token",
+        symbol: "CLOSE",
+        decimals: 9,
+        uri: "https://example.com/close",
+        supply: new anchor.BN(1_000_000_000),
+        maxSupply: new anchor.BN(5_000_000_000),
+        fixedSupply: false,
+        curveType: { constantProduct: {} },
+        metadataStandard: { metaplex: {} },
+      };
+      closeMintAta = await getAssociatedTokenAddress(closeMint.publicKey, trader1.publicKey);
+
+      await buy(tokenParams, trader1, { mint: closeMint, referrer: trader2.publicKey });
+    });
+
+    it("should fail while the ATA still holds tokens", async () => {
+      try {
+        await closeTokenRecord();
+        expect.fail("Should have failed");
+      } catch (error) {
+        expect(error.message).to.include("Token account not empty");
+      }
+    });
+
+    it("should fail with unclaimed creator fees", async () => {
+      await program.methods.openHolderPosition().accountsPartial({
+        holder: trader1.publicKey,
+        mint: closeMint.publicKey,
+        holderMintAta: closeMintAta,
+        bondingCurve: getBondingCurvePDA(closeMint.publicKey),
+        holderPosition: getHolderPositionPDA(closeMint.publicKey, trader1.publicKey),
+        mintTokenProgram: TOKEN_PROGRAM_ID,
+        systemProgram: SystemProgram.programId,
+      }).signers([trader1]).rpc();
+
+      await getOrCreateAssociatedTokenAccount(connection, trader2, closeMint.publicKey, trader2.publicKey);
+      await transfer(
+        connection,
+        trader1,
+        closeMintAta,
+        getAssociatedTokenAddressSync(closeMint.publicKey, trader2.publicKey),
+        trader1,
+        10_000_000
+      );
+
+      const traderMintAccount = await getAccount(connection, closeMintAta);
+      await program.methods.sell(new anchor.BN(traderMintAccount.amount.toString()), new anchor.BN(0)).accountsPartial({
+        trader: trader1.publicKey,
+        config: getConfigPDA(),
+        reserve: getReservePDA(closeMint.publicKey),
+        feeVault: getFeeVaultPDA(),
+        traderReferral: getReferralPDA(trader1.publicKey),
+        referrerReferral: getReferralPDA(trader2.publicKey),
+        mint: closeMint.publicKey,
+        traderMint: closeMintAta,
+        xdegenMint,
+        traderXdegenAta: trader1XdegenAta,
+        bondingCurve: getBondingCurvePDA(closeMint.publicKey),
+        holderPosition: getHolderPositionPDA(closeMint.publicKey, trader1.publicKey),
+        tokenRecord: getTokenRecordPDA(closeMint.publicKey, trader1.publicKey),
+        associatedTokenProgram: anchor.utils.token.ASSOCIATED_PROGRAM_ID,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        mintTokenProgram: TOKEN_PROGRAM_ID,
+        systemProgram: SystemProgram.programId,
+      }).signers([trader1]).rpc();
+
+      try {
+        await closeTokenRecord();
+        expect.fail("Should have failed");
+      } catch (error) {
+        expect(error.message).to.include("Unclaimed creator fees");
+      }
+    });
+
+    it("should close the record and the ATA once everything is burned and claimed", async () => {
+      await program.methods.claimCreatorFees().accountsPartial({
+        creator: trader1.publicKey,
+        config: getConfigPDA(),
+        xdegenMint,
+        feeVault: getFeeVaultPDA(),
+        tokenRecord: getTokenRecordPDA(closeMint.publicKey, trader1.publicKey),
+        creatorXdegenAta: trader1XdegenAta,
+        associatedTokenProgram: anchor.utils.token.ASSOCIATED_PROGRAM_ID,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        systemProgram: SystemProgram.programId,
+      }).signers([trader1]).rpc();
+
+      const lamportsBefore = await connection.getBalance(trader1.publicKey);
+      const signature = await closeTokenRecord();
+
+      const event = (await getCpiEvents(signature)).find((e) => e.name === "tokenRecordClosed");
+      expect(event.data.mint.toBase58()).to.equal(closeMint.publicKey.toBase58());
+      expect(await connection.getAccountInfo(getTokenRecordPDA(closeMint.publicKey, trader1.publicKey))).to.be.null;
+      expect(await connection.getAccountInfo(closeMintAta)).to.be.null;
+      expect(await connection.getBalance(trader1.publicKey)).to.be.greaterThan(lamportsBefore);
+    });
+
+    it("should send the creator fee to the protocol once the record is closed", async () => {
+      const configBefore = await program.account.config.fetch(getConfigPDA());
+
+      const signature = await program.methods.sell(new anchor.BN(10_000_000), new anchor.BN(0)).accountsPartial({
+        trader: trader2.publicKey,
+        config: getConfigPDA(),
+        reserve: getReservePDA(closeMint.publicKey),
+        feeVault: getFeeVaultPDA(),
+        traderReferral: getReferralPDA(trader2.publicKey),
+        referrerReferral: null,
+        mint: closeMint.publicKey,
+        traderMint: getAssociatedTokenAddressSync(closeMint.publicKey, trader2.publicKey),
+        xdegenMint,
+        traderXdegenAta: trader2XdegenAta,
+        bondingCurve: getBondingCurvePDA(closeMint.publicKey),
+        holderPosition: null,
+        tokenRecord: getTokenRecordPDA(closeMint.publicKey, trader1.publicKey),
+        associatedTokenProgram: anchor.utils.token.ASSOCIATED_PROGRAM_ID,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        mintTokenProgram: TOKEN_PROGRAM_ID,
+        systemProgram: SystemProgram.programId,
+      }).signers([trader2]).rpc();
+
+      const event = (await getCpiEvents(signature)).find((e) => e.name === "tokensSold");
+      expect(event.data.creatorFee.toNumber()).to.equal(0);
+      expect(event.data.protocolFee.toNumber()).to.be.greaterThan(0);
+      expect(event.data.balance.toNumber()).to.equal(0);
+
+      const configAfter = await program.account.config.fetch(getConfigPDA());
+      expect(configAfter.creatorFees.toString()).to.equal(configBefore.creatorFees.toString());
+      expect(configAfter.protocolFees.sub(configBefore.protocolFees).toNumber()).to.equal(event.data.protocolFee.toNumber());
+    });
+  });
+
+  describe("AdminMultisig", () => {
+    const signer2 = Keypair.generate();
+    const signer3 = Keypair.generate();
+
+    function getAdminProposalPDA(id: anchor.BN) {
+      return PublicKey.findProgramAddressSync(
+        [Buffer.from("admin_proposal"), id.toArrayLike(Buffer, "le", 8)],
+        program.programId
+      )[0];
+    }
+
+    async function propose(action, proposer: Keypair = admin) {
+      const { proposalCount } = await program.account.config.fetch(getConfigPDA());
+      await program.methods.createAdminProposal(action).accountsPartial({
+        proposer: proposer.publicKey,
+        config: getConfigPDA(),
+        adminProposal: getAdminProposalPDA(proposalCount),
+        systemProgram: SystemProgram.programId,
+      }).signers([proposer]).rpc();
+      return getAdminProposalPDA(proposalCount);
+    }
+
+    function approve(adminProposal: PublicKey, approver: Keypair) {
+      return program.methods.approveAdminProposal().accountsPartial({
+        approver: approver.publicKey,
+        config: getConfigPDA(),
+        adminProposal,
+      }).signers([approver]).rpc();
+    }
+
+    async function execute(adminProposal: PublicKey, withdrawAccounts = null) {
+      const { withdrawalCount } = await program.account.config.fetch(getConfigPDA());
+      return program.methods.executeAdminProposal().accountsPartial({
+        executor: admin.publicKey,
+        config: getConfigPDA(),
+        proposer: admin.publicKey,
+        adminProposal,
+        destination: withdrawAccounts ? withdrawAccounts.destination : null,
+        pendingWithdrawal: withdrawAccounts ? getPendingWithdrawalPDA(withdrawalCount) : null,
+        systemProgram: withdrawAccounts ? SystemProgram.programId : null,
+      }).signers([admin]).rpc();
+    }
+
+    before(async () => {
+      await airdrop(signer2.publicKey);
+      await airdrop(signer3.publicKey);
+    });
+
+    it("should start as a 1-of-1 set holding the admin", async () => {
+      const configAccount = await program.account.config.fetch(getConfigPDA());
+      expect(configAccount.adminSigners.map((k) => k.toBase58())).to.deep.equal([admin.publicKey.toBase58()]);
+      expect(configAccount.adminThreshold).to.equal(1);
+    });
+
+    it("should fail to propose with a wallet outside the signer set", async () => {
+      try {
+        await propose({ setPause: { flags: 1, paused: true } }, trader1);
+        expect.fail("Should have failed");
+      } catch (error) {
+        expect(error.message).to.include("Not admin signer");
+      }
+    });
+
+    it("should fail to propose an invalid signer set", async () => {
+      try {
+        await propose({ setSigners: { signers: [admin.publicKey, signer2.publicKey], threshold: 3 } });
+        expect.fail("Should have failed");
+      } catch (error) {
+        expect(error.message).to.include("Invalid admin signers");
+      }
+    });
+
+    it("should move to a 2-of-3 signer set through a proposal", async () => {
+      const adminProposal = await propose({
+        setSigners: { signers: [admin.publicKey, signer2.publicKey, signer3.publicKey], threshold: 2 }
+      });
+      await execute(adminProposal);
+
+      const configAccount = await program.account.config.fetch(getConfigPDA());
+      expect(configAccount.adminSigners.length).to.equal(3);
+      expect(configAccount.adminThreshold).to.equal(2);
+
+      // executing closes the proposal and refunds the proposer
+      expect(await connection.getAccountInfo(adminProposal)).to.be.null;
+      try {
+        await execute(adminProposal);
+        expect.fail("Should have failed");
+      } catch (error) {
+        expect(error.message).to.include("AccountNotInitialized");
+      }
+    });
+
+    it("should not close a proposal before it expires", async () => {
+      const adminProposal = await propose({ setPause: { flags: 1, paused: true } });
+      try {
+        await program.methods.closeAdminProposal().accountsPartial({
+          proposer: admin.publicKey,
+          adminProposal,
+        }).rpc();
+        expect.fail("Should have failed");
+      } catch (error) {
+        expect(error.message).to.include("Proposal not expired");
+      }
+    });
+
+    it("should block direct admin actions once the multisig is in force", async () => {
+      try {
+        await program.methods.updateConfig({
+          claimAmount: new anchor.BN(1),
+          claimCooldown: null,
+          maxClaimPerWallet: null,
+          protocolFeeBps: null,
+          creatorFeeBps: null,
+          referralFeeBps: null,
+          withdrawDelay: null,
+          withdrawCap: null,
+        }).accountsPartial({
+          authority: admin.publicKey,
+          config: getConfigPDA(),
+        }).signers([admin]).rpc();
+        expect.fail("Should have failed");
+      } catch (error) {
+        expect(error.message).to.include("Multisig required");
+      }
+
+      try {
+        await program.methods.setPause(1, true).accountsPartial({
+          authority: admin.publicKey,
+          config: getConfigPDA(),
+        }).signers([admin]).rpc();
+        expect.fail("Should have failed");
+      } catch (error) {
+        expect(error.message).to.include("Multisig required");
+      }
+
+      try {
+        await program.methods.setRoles(signer2.publicKey, 1, true).accountsPartial({
+          admin: admin.publicKey,
+          config: getConfigPDA(),
+        }).signers([admin]).rpc();
+        expect.fail("Should have failed");
+      } catch (error) {
+        expect(error.message).to.include("Multisig required");
+      }
+
+      // the moderator role initialize gave the admin is held back too
+      const [symbolRecord] = await program.account.symbolRecord.all();
+      try {
+        await program.methods.releaseSymbol().accountsPartial({
+          authority: admin.publicKey,
+          config: getConfigPDA(),
+          symbolRecord: symbolRecord.publicKey,
+        }).signers([admin]).rpc();
+        expect.fail("Should have failed");
+      } catch (error) {
+        expect(error.message).to.include("Multisig required");
+      }
+
+      try {
+        await program.methods.setDelisted(true).accountsPartial({
+          authority: admin.publicKey,
+          config: getConfigPDA(),
+          bondingCurve: getBondingCurvePDA(newMint.publicKey),
+        }).signers([admin]).rpc();
+        expect.fail("Should have failed");
+      } catch (error) {
+        expect(error.message).to.include("Multisig required");
+      }
+    });
+
+    it("should grant roles through a proposal", async () => {
+      const adminProposal = await propose({ setRoles: { member: signer2.publicKey, roles: 2, granted: true } });
+      await approve(adminProposal, signer3);
+      await execute(adminProposal);
+
+      const configAccount = await program.account.config.fetch(getConfigPDA());
+      const member = configAccount.roleMembers.find((m) => m.key.equals(signer2.publicKey));
+      expect(member.roles).to.equal(2);
+
+      const revoke = await propose({ setRoles: { member: signer2.publicKey, roles: 2, granted: false } });
+      await approve(revoke, signer3);
+      await execute(revoke);
+    });
+
+    it("should queue a withdrawal once the threshold is met", async () => {
+      const configBefore = await program.account.config.fetch(getConfigPDA());
+      const amount = anchor.BN.min(configBefore.treasuryBalance, new anchor.BN(1_000_000));
+      const adminProposal = await propose({
+        withdraw: { amount, bucket: { treasury: {} }, destination: adminXdegenATA }
+      });
+
+      try {
+        await execute(adminProposal, { destination: adminXdegenATA });
+        expect.fail("Should have failed");
+      } catch (error) {
+        expect(error.message).to.include("Threshold not met");
+      }
+
+      await approve(adminProposal, signer2);
+      try {
+        await approve(adminProposal, signer2);
+        expect.fail("Should have failed");
+      } catch (error) {
+        expect(error.message).to.include("Already approved");
+      }
+
+      try {
+        await execute(adminProposal, { destination: trader1XdegenAta });
+        expect.fail("Should have failed");
+      } catch (error) {
+        expect(error.message).to.include("Invalid destination");
+      }
+
+      const { withdrawalCount } = await program.account.config.fetch(getConfigPDA());
+      await execute(adminProposal, { destination: adminXdegenATA });
+
+      const pendingWithdrawal = getPendingWithdrawalPDA(withdrawalCount);
+      const pending = await program.account.pendingWithdrawal.fetch(pendingWithdrawal);
+      expect(pending.amount.toString()).to.equal(amount.toString());
+      expect(pending.payer.toBase58()).to.equal(admin.publicKey.toBase58());
+
+      // a proposal only queues the withdrawal, it still waits out the delay
+      try {
+        await program.methods.executeWithdrawal().accountsPartial({
+          authority: signer2.publicKey,
+          config: getConfigPDA(),
+          pendingWithdrawal,
+          payer: admin.publicKey,
+          xdegenMint,
+          vault: getVaultPDA(),
+          destination: adminXdegenATA,
+          tokenProgram: TOKEN_PROGRAM_ID,
+        }).signers([signer2]).rpc();
+        expect.fail("Should have failed");
+      } catch (error) {
+        expect(error.message).to.include("Withdrawal locked");
+      }
+
+      await program.methods.cancelWithdrawal().accountsPartial({
+        authority: signer2.publicKey,
+        config: getConfigPDA(),
+        pendingWithdrawal,
+        payer: admin.publicKey,
+      }).signers([signer2]).rpc();
+      const configAfter = await program.account.config.fetch(getConfigPDA());
+      expect(configAfter.treasuryBalance.toString()).to.equal(configBefore.treasuryBalance.toString());
+    });
+
+    it("should return to a single admin with two approvals", async () => {
+      const adminProposal = await propose({ setSigners: { signers: [admin.publicKey], threshold: 1 } });
+      await approve(adminProposal, signer3);
+      await execute(adminProposal);
+
+      const configAccount = await program.account.config.fetch(getConfigPDA());
+      expect(configAccount.adminSigners.length).to.equal(1);
+      expect(configAccount.adminThreshold).to.equal(1);
+    });
+  });
+
+  describe("Roles", () => {
+    const ROLE_TREASURER = 1;
+    const ROLE_OPERATOR = 2;
+    const ROLE_ALL = 15;
+
+    function setRoles(member: PublicKey, roles: number, granted: boolean, signer: Keypair = admin) {
+      return program.methods.setRoles(member, roles, granted).accountsPartial({
+        admin: signer.publicKey,
+        config: getConfigPDA(),
+      }).signers([signer]).rpc();
+    }
+
+    it("should give the initial admin every role", async () => {
+      const configAccount = await program.account.config.fetch(getConfigPDA());
+      const member = configAccount.roleMembers.find((m) => m.key.equals(admin.publicKey));
+      expect(member.roles).to.equal(ROLE_ALL);
+    });
+
+    it("should let a granted operator update trade tiers until revoked", async () => {
+      const { tradeTiers } = await program.account.config.fetch(getConfigPDA());
+      const signature = await setRoles(trader1.publicKey, ROLE_OPERATOR | ROLE_TREASURER, true);
+      const event = (await getCpiEvents(signature)).find((e) => e.name === "rolesUpdated");
+      expect(event.data.oldRoles).to.equal(0);
+      expect(event.data.newRoles).to.equal(ROLE_OPERATOR | ROLE_TREASURER);
+
+      await program.methods.updateTradeTiers(tradeTiers).accountsPartial({
+        authority: trader1.publicKey,
+        config: getConfigPDA(),
+      }).signers([trader1]).rpc();
+
+      await setRoles(trader1.publicKey, ROLE_OPERATOR, false);
+      try {
+        await program.methods.updateTradeTiers(tradeTiers).accountsPartial({
+          authority: trader1.publicKey,
+          config: getConfigPDA(),
+        }).signers([trader1]).rpc();
+        expect.fail("Should have failed");
+      } catch (error) {
+        expect(error.message).to.include("Missing role");
+      }
+
+      await setRoles(trader1.publicKey, ROLE_TREASURER, false);
+      const configAccount = await program.account.config.fetch(getConfigPDA());
+      expect(configAccount.roleMembers.some((m) => m.key.equals(trader1.publicKey))).to.be.false;
+    });
+
+    it("should fail with unknown role bits", async () => {
+      try {
+        await setRoles(trader1.publicKey, 16, true);
+        expect.fail("Should have failed");
+      } catch (error) {
+        expect(error.message).to.include("Invalid roles");
+      }
+    });
+
+    it("should fail with unauthorized", async () => {
+      try {
+        await setRoles(trader1.publicKey, ROLE_OPERATOR, true, trader1);
+        expect.fail("Should have failed");
+      } catch (error) {
+        expect(error.message).to.include("Unauthorized");
+      }
+    });
+  });
+
+  describe("Delisting", () => {
+    function setDelisted(delisted: boolean, signer: Keypair = admin) {
+      return program.methods.setDelisted(delisted).accountsPartial({
+        authority: signer.publicKey,
+        config: getConfigPDA(),
+        bondingCurve: getBondingCurvePDA(newMint.publicKey),
+      }).signers([signer]).rpc();
+    }
+
+    it("should fail to delist without the moderator role", async () => {
+      try {
+        await setDelisted(true, trader2);
+        expect.fail("Should have failed");
+      } catch (error) {
+        expect(error.message).to.include("Missing role");
+      }
+    });
+
+    it("should halt minting a delisted token until it is relisted", async () => {
+      const signature = await setDelisted(true);
+      const event = (await getCpiEvents(signature)).find((e) => e.name === "delistingUpdated");
+      expect(event.data.mint.toBase58()).to.equal(newMint.publicKey.toBase58());
+      expect(event.data.delisted).to.be.true;
+
+      try {
+        await mintMore(newMint.publicKey, 1_000_000, { referrer: trader2.publicKey });
+        expect.fail("Should have failed");
+      } catch (error) {
+        expect(error.message).to.include("Token delisted");
+      }
+
+      await setDelisted(false);
+      const bondingCurve = await program.account.bondingCurve.fetch(getBondingCurvePDA(newMint.publicKey));
+      expect(bondingCurve.delisted).to.be.false;
+      await mintMore(newMint.publicKey, 1_000_000, { referrer: trader2.publicKey });
+    });
   });
-})
+})
\ No newline at end of file